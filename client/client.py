@@ -1,5 +1,5 @@
 from asyncio.windows_events import NULL
-from typing import Dict, List
+from typing import Dict, List, Optional
 from xml.etree.ElementTree import QName
 import pygame
 import math
@@ -11,6 +11,14 @@ import threading
 
 BLACK = (0, 0, 0)
 RED = (235, 72, 55)
+GRAY = (128, 128, 128)
+DARK_GRAY = (64, 64, 64)
+
+# Rounds of the pacts offered with the "Pact" button
+PACT_ROUNDS = 3
+
+# Owner id of territories that belong to no player
+NEUTRAL_OWNER = 4294967295
 
 def parseGameboard(json_str):
     json_map = json.loads(json_str)
@@ -19,7 +27,10 @@ def parseGameboard(json_str):
     for id_str in json_map['territory_map']:
         id = int(id_str)
         terr_data = json_map['territory_map'][id_str]
-        curr_territory = Territory(terr_data['id'], terr_data['num_dice'], terr_data['owner_id'], terr_data['neighbors'])
+        curr_territory = Territory(terr_data['id'], terr_data['num_dice'], terr_data['owner_id'], terr_data['neighbors'],
+                                   terr_data.get('centroid'), terr_data.get('polygon', []), terr_data.get('hex_cells', []),
+                                   terr_data.get('name'), terr_data.get('continent'), terr_data.get('capital', False),
+                                   terr_data.get('hidden', False))
         territory_map[id] = curr_territory
 
     return territory_map
@@ -42,23 +53,52 @@ def draw_gameboard():
     coord_map = {}
     rect_map = {}
 
+    hex_centers = [hex_center(cell) for terr in territory_map.values() for cell in terr.hex_cells]
+    hex_bounds = None
+    if len(hex_centers) > 0:
+        hex_bounds = (min(c[0] for c in hex_centers), min(c[1] for c in hex_centers),
+                      max(c[0] for c in hex_centers), max(c[1] for c in hex_centers))
+
     for curr_id, cur_terr in territory_map.items():
         x = (radius * math.sin(rotation)) + x_origin
         y = (radius * math.cos(rotation)) + y_origin
 
         terr_color = (0, 0, 255)
-        if cur_terr.owner_id !=  0:
+        if cur_terr.hidden:
+            terr_color = DARK_GRAY
+        elif cur_terr.owner_id == NEUTRAL_OWNER:
+            terr_color = GRAY
+        elif cur_terr.owner_id !=  0:
             terr_color = (0, 255, 0)
 
-        rect = pygame.draw.circle(screen, terr_color, (x, y), 50)
+        if cur_terr.centroid is not None:
+            (x, y) = to_screen(cur_terr.centroid)
+            if len(cur_terr.polygon) > 0:
+                pygame.draw.polygon(screen, terr_color, [to_screen(point) for point in cur_terr.polygon])
+                pygame.draw.polygon(screen, BLACK, [to_screen(point) for point in cur_terr.polygon], 2)
+            for cell in cur_terr.hex_cells:
+                draw_hex(cell, hex_bounds, terr_color)
+            rect = pygame.draw.circle(screen, terr_color, (x, y), 10)
+        else:
+            rect = pygame.draw.circle(screen, terr_color, (x, y), 50)
         
         rect_map[cur_terr.id] = rect
         coord_map[cur_terr.id] = (x,y)
 
-        terr_id_text_img = font.render(str(cur_terr.id), True, (0, 0, 0))
+        terr_label = str(cur_terr.id)
+        if cur_terr.name is not None:
+            terr_label = "{} {}".format(cur_terr.id, cur_terr.name)
+        if cur_terr.capital:
+            terr_label += " (capital)"
+        if len(game_state.teams) > cur_terr.owner_id:
+            terr_label += " [team {}]".format(game_state.teams[cur_terr.owner_id])
+        terr_id_text_img = font.render(terr_label, True, (0, 0, 0))
         screen.blit(terr_id_text_img, (x, y))
 
-        terr_id_text_img = font.render(str(cur_terr.num_dice), True, (255, 255, 255))
+        dice_label = str(cur_terr.num_dice)
+        if cur_terr.hidden:
+            dice_label = "?"
+        terr_id_text_img = font.render(dice_label, True, (255, 255, 255))
         screen.blit(terr_id_text_img, (x, y+10))
 
         rotation += delta_rotation
@@ -75,15 +115,69 @@ def draw_gameboard():
         global RED
         attack_button_color = RED
 
+        if game_state.attack_odds >= 0:
+            odds_text_img = font.render("Win chance: {0:.0%}".format(game_state.attack_odds), True, (0, 0, 0))
+            screen.blit(odds_text_img, (10, 210))
+
     global attack_rect
     attack_rect = pygame.draw.circle(screen, attack_button_color, (51, 51), 50)
     attack_text_img = font.render("Attack", True, (255, 255, 255), (0,0,0))
     screen.blit(attack_text_img, (46, 41))
 
+    global end_turn_rect
+    end_turn_rect = pygame.draw.circle(screen, BLACK, (51, 153), 50)
+    end_turn_text_img = font.render("End Turn", True, (255, 255, 255), (0,0,0))
+    screen.blit(end_turn_text_img, (28, 147))
+
+    global move_rect
+    move_rect = pygame.draw.circle(screen, BLACK, (51, 306), 50)
+    move_text_img = font.render("Move", True, (255, 255, 255), (0,0,0))
+    screen.blit(move_text_img, (40, 300))
+
+    global place_rect
+    place_rect = pygame.draw.circle(screen, BLACK, (51, 408), 50)
+    place_text_img = font.render("Place", True, (255, 255, 255), (0,0,0))
+    screen.blit(place_text_img, (39, 402))
+
+    global pact_rect
+    pact_rect = pygame.draw.circle(screen, BLACK, (51, 510), 50)
+    pact_text_img = font.render("Pact", True, (255, 255, 255), (0,0,0))
+    screen.blit(pact_text_img, (42, 504))
+
+    for index, pact in enumerate(game_state.pacts):
+        pact_text = "Pact {0}-{1} until turn {2}".format(pact['first_player_id'], pact['second_player_id'], pact['expires_on_turn'])
+        screen.blit(font.render(pact_text, True, (0, 0, 0)), (110, 10 + 12 * index))
+
+    if game_state.pending_reinforcements > 0:
+        reinforcements_text_img = font.render("Player {0} places {1} dice".format(game_state.reinforcing_player, game_state.pending_reinforcements), True, (0, 0, 0))
+        screen.blit(reinforcements_text_img, (10, 570))
+
     pygame.display.flip()
 
     game_state.rect_map = rect_map
 
+# Generated map coordinates are in the unit square, drawn to the right of the buttons
+def to_screen(point):
+    map_left = 110
+    map_top = 10
+    map_width = window_width - map_left - 10
+    map_height = window_height - map_top - 10
+    return (map_left + point[0] * map_width, map_top + point[1] * map_height)
+
+def hex_center(cell):
+    (q, r) = cell
+    return (math.sqrt(3) * (q + r / 2.0), 1.5 * r)
+
+def draw_hex(cell, hex_bounds, color):
+    (min_x, min_y, max_x, max_y) = hex_bounds
+    (cx, cy) = hex_center(cell)
+    center = ((cx - min_x) / max(max_x - min_x, 1.0), (cy - min_y) / max(max_y - min_y, 1.0))
+    (x, y) = to_screen(center)
+    size = (window_width - 120) / max(max_x - min_x + 2.0, 1.0)
+    corners = [(x + size * math.cos(math.radians(60 * i - 30)), y + size * math.sin(math.radians(60 * i - 30))) for i in range(6)]
+    pygame.draw.polygon(screen, color, corners)
+    pygame.draw.polygon(screen, BLACK, corners, 1)
+
 def handle_click():
     global game_state
 
@@ -100,6 +194,27 @@ def handle_click():
             print("Clicked \"Attack\"")
             handle_attack()
 
+    global end_turn_rect
+    if end_turn_rect.collidepoint(x_pos, y_pos):
+            print("Clicked \"End Turn\"")
+            send_message('EndTurn')
+
+    global move_rect
+    if move_rect.collidepoint(x_pos, y_pos):
+            print("Clicked \"Move\"")
+            handle_move()
+
+    global place_rect
+    if place_rect.collidepoint(x_pos, y_pos):
+            print("Clicked \"Place\"")
+            if game_state.attack_from >= 0:
+                send_message('Place;' + str(game_state.attack_from) + ';1')
+
+    global pact_rect
+    if pact_rect.collidepoint(x_pos, y_pos):
+            print("Clicked \"Pact\"")
+            handle_pact()
+
 def handle_territory_selected(clicked_territory):
     global game_state
 
@@ -107,6 +222,8 @@ def handle_territory_selected(clicked_territory):
         game_state.attack_from = clicked_territory
     elif game_state.attack_from >= 0 and game_state.attack_to < 0:
         game_state.attack_to = clicked_territory
+        game_state.attack_odds = -1.0
+        send_message('Odds;' + str(game_state.attack_from) + ';' + str(game_state.attack_to))
     elif game_state.attack_from >= 0 and game_state.attack_to >= 0:
         game_state.attack_from = clicked_territory
         game_state.attack_to = -1
@@ -131,6 +248,31 @@ def handle_attack():
     if can_attack:
         send_message('Attack;' + str(game_state.attack_from) + ';' + str(game_state.attack_to))
 
+# Moves every die but one from the first selected territory to the second
+def handle_move():
+    global game_state
+
+    if game_state.attack_from < 0 or game_state.attack_to < 0:
+        print("Cannot move: need to select two territories")
+        return
+
+    count = game_state.territory_map[game_state.attack_from].num_dice - 1
+    send_message('Move;' + str(game_state.attack_from) + ';' + str(game_state.attack_to) + ';' + str(count))
+
+# Accepts the pact offered by the owner of the selected territory, or offers them one
+def handle_pact():
+    global game_state
+
+    if game_state.attack_from < 0:
+        print("Cannot propose pact: need to select a territory of the other player")
+        return
+
+    owner_id = game_state.territory_map[game_state.attack_from].owner_id
+    if any(proposal['proposer'] == owner_id and proposal['partner'] == game_state.player_id
+           for proposal in game_state.proposals):
+        send_message('Accept;' + str(owner_id))
+    else:
+        send_message('Propose;' + str(owner_id) + ';' + str(PACT_ROUNDS))
 
 def send_message(message):
     global socket
@@ -164,7 +306,24 @@ def parse_message(message):
         match split_msg[0]:
             case "Gameboard":
                 game_state.territory_map = parseGameboard(split_msg[1])
+                board_json = json.loads(split_msg[1])
+                game_state.reinforcing_player = board_json['current_player_id']
+                game_state.pending_reinforcements = board_json.get('pending_reinforcements', 0)
+                game_state.teams = board_json.get('teams', [])
+                game_state.pacts = board_json.get('pacts', [])
+                game_state.proposals = board_json.get('proposals', [])
+                draw_gameboard()
+            case "Reinforcements":
+                game_state.reinforcing_player = int(split_msg[1])
+                game_state.pending_reinforcements = int(split_msg[2])
                 draw_gameboard()
+            case "Seat":
+                game_state.player_id = int(split_msg[1])
+            case "Event":
+                print("Turn {0}: {1}".format(split_msg[1], " ".join(split_msg[2:])))
+            case "Odds":
+                if int(split_msg[1]) == game_state.attack_from and int(split_msg[2]) == game_state.attack_to:
+                    game_state.attack_odds = float(split_msg[3])
     
 
 
@@ -175,6 +334,14 @@ class Territory:
     num_dice: int
     owner_id: int
     neighbors: List
+    centroid: Optional[List[float]] = None
+    polygon: List = dataclasses.field(default_factory=list)
+    hex_cells: List = dataclasses.field(default_factory=list)
+    name: Optional[str] = None
+    continent: Optional[int] = None
+    capital: bool = False
+    # Set on territories hidden by the fog of war
+    hidden: bool = False
 
 @dataclasses.dataclass
 class GameState:
@@ -182,6 +349,14 @@ class GameState:
     attack_from: int
     territory_map: Dict[int, Territory]
     rect_map: Dict[int, pygame.Rect]
+    attack_odds: float = -1.0
+    # Seat of this client, -1 while spectating
+    player_id: int = -1
+    reinforcing_player: int = -1
+    pending_reinforcements: int = 0
+    teams: List = dataclasses.field(default_factory=list)
+    pacts: List = dataclasses.field(default_factory=list)
+    proposals: List = dataclasses.field(default_factory=list)
 
 pygame.init()
 
@@ -209,6 +384,10 @@ socket_thread.start()
 #territory_map = parseGameboard(json_gameboard)
 
 attack_rect = NULL
+end_turn_rect = NULL
+move_rect = NULL
+place_rect = NULL
+pact_rect = NULL
 
 while running:
 
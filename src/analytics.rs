@@ -0,0 +1,371 @@
+use crate::gameboard::Gameboard;
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerBorder {
+    pub player_id: u32,
+    /// Territories next to at least one territory of another owner
+    pub border_territories: u32,
+    /// Edges between the player and any other owner
+    pub border_edges: u32,
+}
+
+/// Structure of a board's adjacency graph, for map design reviews.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardReport {
+    pub num_territories: u32,
+    pub num_edges: u32,
+    /// `degree_histogram[d]` is the number of territories with `d` neighbors
+    pub degree_histogram: Vec<u32>,
+    /// Longest shortest path between two territories, None if the board is disconnected
+    pub diameter: Option<u32>,
+    /// Territories whose capture would split the board
+    pub articulation_points: Vec<u32>,
+    /// Edges whose removal would split the board, smaller id first
+    pub bridges: Vec<(u32, u32)>,
+    pub borders: Vec<PlayerBorder>,
+}
+
+impl BoardReport {
+    pub fn average_degree(&self) -> f64 {
+        if self.num_territories == 0 {
+            return 0.0;
+        }
+
+        2.0 * f64::from(self.num_edges) / f64::from(self.num_territories)
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text: String = format!(
+            "{} territories, {} edges, {:.2} neighbors on average\n",
+            self.num_territories,
+            self.num_edges,
+            self.average_degree()
+        );
+
+        match self.diameter {
+            Some(diameter) => text.push_str(&format!("Diameter: {}\n", diameter)),
+            None => text.push_str("Diameter: none, the board is disconnected\n"),
+        }
+
+        text.push_str("Degree histogram:\n");
+        for (cur_degree, cur_count) in self.degree_histogram.iter().enumerate() {
+            if *cur_count > 0 {
+                text.push_str(&format!("{:>4} neighbors: {}\n", cur_degree, cur_count));
+            }
+        }
+
+        text.push_str(&format!(
+            "Articulation points ({}): {:?}\n",
+            self.articulation_points.len(),
+            self.articulation_points
+        ));
+        text.push_str(&format!(
+            "Bridges ({}): {:?}\n",
+            self.bridges.len(),
+            self.bridges
+        ));
+
+        text.push_str(&format!(
+            "{:<8} {:>18} {:>13}\n",
+            "player", "border territories", "border edges"
+        ));
+        for cur_border in &self.borders {
+            text.push_str(&format!(
+                "{:<8} {:>18} {:>13}\n",
+                cur_border.player_id, cur_border.border_territories, cur_border.border_edges
+            ));
+        }
+
+        text
+    }
+}
+
+pub fn analyze(gameboard: &Gameboard) -> BoardReport {
+    let (terr_ids, adjacency): (Vec<u32>, Vec<Vec<usize>>) = adjacency(gameboard);
+    let (articulation_points, bridges): (Vec<u32>, Vec<(u32, u32)>) =
+        chokepoints(&terr_ids, &adjacency);
+
+    let max_degree: usize = adjacency.iter().map(|edges| edges.len()).max().unwrap_or(0);
+    let mut degree_histogram: Vec<u32> = vec![0; max_degree + 1];
+    for cur_edges in &adjacency {
+        degree_histogram[cur_edges.len()] += 1;
+    }
+
+    BoardReport {
+        num_territories: terr_ids.len() as u32,
+        num_edges: adjacency
+            .iter()
+            .map(|edges| edges.len() as u32)
+            .sum::<u32>()
+            / 2,
+        degree_histogram,
+        diameter: diameter(&adjacency),
+        articulation_points,
+        bridges,
+        borders: (0..gameboard.num_players)
+            .map(|cur_player_id| player_border(gameboard, cur_player_id))
+            .collect(),
+    }
+}
+
+// Sorted territory ids and, by index into them, the mutual edges between
+// existing territories. Self-loops and repeated neighbors are dropped.
+fn adjacency(gameboard: &Gameboard) -> (Vec<u32>, Vec<Vec<usize>>) {
+    let mut terr_ids: Vec<u32> = gameboard.territory_map.keys().copied().collect();
+    terr_ids.sort_unstable();
+
+    let index_of: HashMap<u32, usize> = terr_ids
+        .iter()
+        .enumerate()
+        .map(|(cur_index, cur_id)| (*cur_id, cur_index))
+        .collect();
+
+    let adjacency: Vec<Vec<usize>> = terr_ids
+        .iter()
+        .map(|cur_id| {
+            let mut edges: Vec<usize> = gameboard.territory_map[cur_id]
+                .neighbors
+                .iter()
+                .filter(|neighbor| *neighbor != cur_id)
+                .filter(|neighbor| {
+                    gameboard
+                        .territory_map
+                        .get(neighbor)
+                        .is_some_and(|neighbor_terr| neighbor_terr.neighbors.contains(cur_id))
+                })
+                .map(|neighbor| index_of[neighbor])
+                .collect();
+            edges.sort_unstable();
+            edges.dedup();
+            edges
+        })
+        .collect();
+
+    (terr_ids, adjacency)
+}
+
+// Tarjan's low-link algorithm, with an explicit stack so large boards cannot
+// overflow the call stack
+fn chokepoints(terr_ids: &[u32], adjacency: &[Vec<usize>]) -> (Vec<u32>, Vec<(u32, u32)>) {
+    let num_territories: usize = adjacency.len();
+    let mut discovered: Vec<Option<u32>> = vec![None; num_territories];
+    let mut low: Vec<u32> = vec![0; num_territories];
+    let mut parent: Vec<Option<usize>> = vec![None; num_territories];
+    let mut is_articulation: Vec<bool> = vec![false; num_territories];
+    let mut bridges: Vec<(u32, u32)> = Vec::new();
+    let mut timer: u32 = 0;
+
+    for root in 0..num_territories {
+        if discovered[root].is_some() {
+            continue;
+        }
+
+        discovered[root] = Some(timer);
+        low[root] = timer;
+        timer += 1;
+        let mut root_children: u32 = 0;
+        let mut stack: Vec<(usize, usize)> = vec![(root, 0)];
+
+        while let Some((cur_index, next_edge)) = stack.last_mut() {
+            let cur_index: usize = *cur_index;
+
+            if *next_edge < adjacency[cur_index].len() {
+                let neighbor: usize = adjacency[cur_index][*next_edge];
+                *next_edge += 1;
+
+                match discovered[neighbor] {
+                    None => {
+                        parent[neighbor] = Some(cur_index);
+                        discovered[neighbor] = Some(timer);
+                        low[neighbor] = timer;
+                        timer += 1;
+                        if cur_index == root {
+                            root_children += 1;
+                        }
+                        stack.push((neighbor, 0));
+                    }
+                    Some(neighbor_time) if parent[cur_index] != Some(neighbor) => {
+                        low[cur_index] = low[cur_index].min(neighbor_time);
+                    }
+                    Some(_) => {}
+                }
+                continue;
+            }
+
+            stack.pop();
+            if let Some(parent_index) = parent[cur_index] {
+                low[parent_index] = low[parent_index].min(low[cur_index]);
+
+                let parent_time: u32 = discovered[parent_index].unwrap();
+                if low[cur_index] > parent_time {
+                    let (first, second) = (terr_ids[parent_index], terr_ids[cur_index]);
+                    bridges.push((first.min(second), first.max(second)));
+                }
+                if parent_index != root && low[cur_index] >= parent_time {
+                    is_articulation[parent_index] = true;
+                }
+            }
+        }
+
+        if root_children > 1 {
+            is_articulation[root] = true;
+        }
+    }
+
+    let articulation_points: Vec<u32> = terr_ids
+        .iter()
+        .zip(&is_articulation)
+        .filter(|(_id, is_articulation)| **is_articulation)
+        .map(|(id, _is_articulation)| *id)
+        .collect();
+    bridges.sort_unstable();
+
+    (articulation_points, bridges)
+}
+
+// Breadth-first search from every territory
+fn diameter(adjacency: &[Vec<usize>]) -> Option<u32> {
+    let mut diameter: u32 = 0;
+
+    for start in 0..adjacency.len() {
+        let mut distances: Vec<Option<u32>> = vec![None; adjacency.len()];
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        distances[start] = Some(0);
+        queue.push_back(start);
+
+        while let Some(cur_index) = queue.pop_front() {
+            let cur_distance: u32 = distances[cur_index].unwrap();
+            diameter = diameter.max(cur_distance);
+
+            for cur_neighbor in &adjacency[cur_index] {
+                if distances[*cur_neighbor].is_none() {
+                    distances[*cur_neighbor] = Some(cur_distance + 1);
+                    queue.push_back(*cur_neighbor);
+                }
+            }
+        }
+
+        if distances.iter().any(|distance| distance.is_none()) {
+            return None;
+        }
+    }
+
+    Some(diameter)
+}
+
+fn player_border(gameboard: &Gameboard, player_id: u32) -> PlayerBorder {
+    let mut border_territories: u32 = 0;
+    let mut border_edges: u32 = 0;
+
+    for cur_terr in gameboard.territory_map.values() {
+        if cur_terr.owner_id != player_id {
+            continue;
+        }
+
+        let num_foreign: u32 = cur_terr
+            .neighbors
+            .iter()
+            .filter_map(|neighbor| gameboard.territory_map.get(neighbor))
+            .filter(|neighbor_terr| neighbor_terr.owner_id != player_id)
+            .count() as u32;
+
+        if num_foreign > 0 {
+            border_territories += 1;
+            border_edges += num_foreign;
+        }
+    }
+
+    PlayerBorder {
+        player_id,
+        border_territories,
+        border_edges,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameboard::{BoardConfig, Territory};
+
+    // 0 - 1 - 2 with a triangle 2, 3, 4 hanging off the end
+    fn lollipop() -> Gameboard {
+        let neighbors: [Vec<u32>; 5] = [vec![1], vec![0, 2], vec![1, 3, 4], vec![2, 4], vec![2, 3]];
+        let owners: [u32; 5] = [0, 0, 1, 1, 0];
+
+        let territory_map = (0..5)
+            .map(|cur_id| {
+                (
+                    cur_id,
+                    Territory {
+                        id: cur_id,
+                        num_dice: 1,
+                        owner_id: owners[cur_id as usize],
+                        neighbors: neighbors[cur_id as usize].clone(),
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect();
+
+        Gameboard::from_territory_map(territory_map, 2)
+    }
+
+    #[test]
+    fn lollipop_report() {
+        let report: BoardReport = analyze(&lollipop());
+
+        assert_eq!(5, report.num_territories);
+        assert_eq!(5, report.num_edges);
+        assert_eq!(vec![0, 1, 3, 1], report.degree_histogram);
+        assert_eq!(Some(3), report.diameter);
+        assert_eq!(vec![1, 2], report.articulation_points);
+        assert_eq!(vec![(0, 1), (1, 2)], report.bridges);
+        assert_eq!(
+            vec![
+                PlayerBorder {
+                    player_id: 0,
+                    border_territories: 2,
+                    border_edges: 3
+                },
+                PlayerBorder {
+                    player_id: 1,
+                    border_territories: 2,
+                    border_edges: 3
+                },
+            ],
+            report.borders
+        );
+    }
+
+    #[test]
+    fn disconnected_board_has_no_diameter() {
+        let mut gameboard: Gameboard = lollipop();
+        gameboard
+            .territory_map
+            .get_mut(&0)
+            .unwrap()
+            .neighbors
+            .clear();
+        gameboard.territory_map.get_mut(&1).unwrap().neighbors = vec![2];
+
+        let report: BoardReport = analyze(&gameboard);
+
+        assert_eq!(None, report.diameter);
+        assert_eq!(vec![2], report.articulation_points);
+        assert_eq!(vec![(1, 2)], report.bridges);
+    }
+
+    #[test]
+    fn generated_bridges_are_found() {
+        let mut config: BoardConfig = BoardConfig::new(2, 12, 24);
+        config.seed = Some(6);
+        config.topology.num_continents = 4;
+        config.topology.num_bridges = Some(2);
+
+        let report: BoardReport = analyze(&Gameboard::from_config(&config));
+
+        assert_eq!(2, report.bridges.len());
+        assert!(report.diameter.is_some());
+    }
+}
@@ -0,0 +1,83 @@
+//! Headless tournament between bot strategies. Games are played in-process, with no
+//! network, and summarized as a table and optionally as CSV.
+
+use mini_risk::tournament::{self, TournamentConfig, TournamentReport};
+use std::collections::HashMap;
+use std::env::{self};
+use std::fs;
+
+const USAGE: &str = "Usage: mini-risk-tournament [--strategies <a,b,...>] [--games <n>] [--territories <n>] [--dice <n>]
+                            [--max-dice <n>] [--max-turns <n>] [--seed <n>] [--csv <path>]
+
+The same options and seed always give the same report, except with time-limited
+bots such as mcts:250ms, whose searches depend on how fast the machine is.";
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    if args.iter().any(|arg| arg == "--help") {
+        panic!("{}", USAGE);
+    }
+
+    run_tournament(&parse_options(&args[1..]));
+}
+
+fn run_tournament(options: &HashMap<String, String>) {
+    let mut config: TournamentConfig = TournamentConfig::default();
+
+    if let Some(strategies) = options.get("strategies") {
+        config.strategies = strategies.split(',').map(|name| name.to_owned()).collect();
+    }
+    if let Some(num_games) = options.get("games") {
+        config.num_games = num_games
+            .parse()
+            .expect("num games must be a positive integer");
+    }
+    if let Some(num_territories) = options.get("territories") {
+        config.num_territories_per_player = num_territories
+            .parse()
+            .expect("num territories per player must be a positive integer");
+    }
+    if let Some(num_dice) = options.get("dice") {
+        config.num_dice_per_player = num_dice
+            .parse()
+            .expect("num dice per player must be a positive integer");
+    }
+    if let Some(max_dice) = options.get("max-dice") {
+        config.max_dice = max_dice
+            .parse()
+            .expect("max dice must be a positive integer");
+    }
+    if let Some(max_turns) = options.get("max-turns") {
+        config.max_turns = max_turns
+            .parse()
+            .expect("max turns must be a positive integer");
+    }
+    if let Some(seed) = options.get("seed") {
+        config.seed = seed.parse().expect("seed must be a non-negative integer");
+    }
+
+    let report: TournamentReport = tournament::run(&config).unwrap_or_else(|err| panic!("{}", err));
+    print!("{}", report.to_table());
+
+    if let Some(csv_path) = options.get("csv") {
+        fs::write(csv_path, report.to_csv()).expect("Unable to write the CSV report");
+        println!("Wrote CSV report to {}", csv_path);
+    }
+}
+
+fn parse_options(args: &[String]) -> HashMap<String, String> {
+    let mut options: HashMap<String, String> = HashMap::new();
+
+    for cur_pair in args.chunks(2) {
+        let name: &str = cur_pair[0]
+            .strip_prefix("--")
+            .unwrap_or_else(|| panic!("Unexpected argument: {}\n{}", cur_pair[0], USAGE));
+        let value: &String = cur_pair
+            .get(1)
+            .unwrap_or_else(|| panic!("Missing value for --{}", name));
+        options.insert(name.to_owned(), value.to_owned());
+    }
+
+    options
+}
@@ -0,0 +1,585 @@
+use crate::odds;
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, OnceLock};
+
+pub const DEFAULT_DIE_FACES: u32 = 6;
+/// Largest die supported, a d20. Classic odds enumerate every roll of three dice.
+pub const MAX_DIE_FACES: u32 = 20;
+const CLASSIC_ATTACK_DICE: u32 = 3;
+const CLASSIC_DEFENSE_DICE: u32 = 2;
+
+// Round tables are the slow part of classic odds, so they are kept for every dice seen
+type RoundTables = HashMap<(u32, u32), Vec<f64>>;
+static ROUND_TABLES: OnceLock<Mutex<HashMap<DiceRules, RoundTables>>> = OnceLock::new();
+
+/// Who wins when both sides roll the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum TiePolicy {
+    #[default]
+    Defender,
+    Attacker,
+    Reroll,
+}
+
+impl TiePolicy {
+    pub fn from_name(name: &str) -> Option<TiePolicy> {
+        match name {
+            "defender" => Some(TiePolicy::Defender),
+            "attacker" => Some(TiePolicy::Attacker),
+            "reroll" => Some(TiePolicy::Reroll),
+            _ => None,
+        }
+    }
+}
+
+/// The dice every combat mode rolls with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiceRules {
+    pub die_faces: u32,
+    pub ties: TiePolicy,
+    /// Each territory keeps one die at home that does not roll. Classic combat
+    /// always leaves one attacking die behind, this only changes sum combat.
+    pub leave_one_behind: bool,
+}
+
+impl Default for DiceRules {
+    fn default() -> DiceRules {
+        DiceRules {
+            die_faces: DEFAULT_DIE_FACES,
+            ties: TiePolicy::Defender,
+            leave_one_behind: true,
+        }
+    }
+}
+
+impl DiceRules {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.die_faces < 2 {
+            return Err(format!(
+                "Dice need at least 2 faces, not {}",
+                self.die_faces
+            ));
+        }
+        if self.die_faces > MAX_DIE_FACES {
+            return Err(format!(
+                "Dice can have at most {} faces, not {}",
+                MAX_DIE_FACES, self.die_faces
+            ));
+        }
+
+        Ok(())
+    }
+
+    // Dice a territory holding `num_dice` rolls in sum combat
+    pub fn num_rolled(&self, num_dice: u32) -> u32 {
+        if self.leave_one_behind {
+            num_dice.saturating_sub(1)
+        } else {
+            num_dice
+        }
+    }
+
+    fn roll<R: Rng + ?Sized>(&self, rng: &mut R) -> u32 {
+        rng.gen_range(1..=self.die_faces)
+    }
+}
+
+/// Dice left on both territories once an attack is over. When `captured` is
+/// set, the attacker moves dice into the defending territory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BattleOutcome {
+    pub attacker_dice: u32,
+    pub defender_dice: u32,
+    pub captured: bool,
+}
+
+/// How a single attack is resolved.
+pub trait CombatRules {
+    fn name(&self) -> &str;
+
+    fn resolve(
+        &self,
+        attacker_dice: u32,
+        defender_dice: u32,
+        rng: &mut dyn RngCore,
+    ) -> BattleOutcome;
+
+    // Chance that `resolve` ends with a capture
+    fn success_probability(&self, attacker_dice: u32, defender_dice: u32) -> f64;
+}
+
+/// DiceWars style: both sides roll their dice and the attacker has to beat
+/// the defender's sum. The attacker is left with one die either way.
+pub struct SumCombat {
+    pub dice: DiceRules,
+}
+
+impl CombatRules for SumCombat {
+    fn name(&self) -> &str {
+        "sum"
+    }
+
+    fn resolve(
+        &self,
+        attacker_dice: u32,
+        defender_dice: u32,
+        rng: &mut dyn RngCore,
+    ) -> BattleOutcome {
+        let captured: bool = sum_battle(attacker_dice, defender_dice, &self.dice, rng);
+
+        BattleOutcome {
+            attacker_dice: if captured { attacker_dice } else { 1 },
+            defender_dice,
+            captured,
+        }
+    }
+
+    fn success_probability(&self, attacker_dice: u32, defender_dice: u32) -> f64 {
+        odds::sum_success_probability(attacker_dice, defender_dice, &self.dice)
+    }
+}
+
+/// Classic Risk: up to 3 attack dice against up to 2 defense dice, compared
+/// highest to highest. Each comparison costs the loser one die, a tied
+/// comparison follows `dice.ties`. With `continued` the attack repeats until
+/// one side runs out.
+pub struct ClassicCombat {
+    pub continued: bool,
+    pub dice: DiceRules,
+}
+
+impl CombatRules for ClassicCombat {
+    fn name(&self) -> &str {
+        if self.continued {
+            "classic:blitz"
+        } else {
+            "classic"
+        }
+    }
+
+    fn resolve(
+        &self,
+        attacker_dice: u32,
+        defender_dice: u32,
+        rng: &mut dyn RngCore,
+    ) -> BattleOutcome {
+        let mut attacker_left: u32 = attacker_dice;
+        let mut defender_left: u32 = defender_dice;
+
+        while attacker_left > 1 && defender_left > 0 {
+            let mut attack_rolls: Vec<u32> = (0..(attacker_left - 1).min(CLASSIC_ATTACK_DICE))
+                .map(|_cur_die| self.dice.roll(rng))
+                .collect();
+            let mut defense_rolls: Vec<u32> = (0..defender_left.min(CLASSIC_DEFENSE_DICE))
+                .map(|_cur_die| self.dice.roll(rng))
+                .collect();
+            attack_rolls.sort_unstable_by(|first, second| second.cmp(first));
+            defense_rolls.sort_unstable_by(|first, second| second.cmp(first));
+
+            for (cur_attack, cur_defense) in attack_rolls.iter().zip(&defense_rolls) {
+                let mut attack_roll: u32 = *cur_attack;
+                let mut defense_roll: u32 = *cur_defense;
+                // A tied comparison is settled by one fresh die each
+                while attack_roll == defense_roll && self.dice.ties == TiePolicy::Reroll {
+                    attack_roll = self.dice.roll(rng);
+                    defense_roll = self.dice.roll(rng);
+                }
+
+                let attacker_wins: bool = attack_roll > defense_roll
+                    || (attack_roll == defense_roll && self.dice.ties == TiePolicy::Attacker);
+                if attacker_wins {
+                    defender_left -= 1;
+                } else {
+                    attacker_left -= 1;
+                }
+            }
+
+            if !self.continued {
+                break;
+            }
+        }
+
+        BattleOutcome {
+            attacker_dice: attacker_left,
+            defender_dice: defender_left,
+            captured: defender_left == 0,
+        }
+    }
+
+    fn success_probability(&self, attacker_dice: u32, defender_dice: u32) -> f64 {
+        let mut memo: HashMap<(u32, u32), f64> = HashMap::new();
+        let mut round_tables = ROUND_TABLES
+            .get_or_init(|| Mutex::new(HashMap::new()))
+            .lock()
+            .unwrap();
+        classic_capture_probability(
+            attacker_dice,
+            defender_dice,
+            self.continued,
+            &self.dice,
+            &mut memo,
+            round_tables.entry(self.dice).or_default(),
+        )
+    }
+}
+
+/// Which `CombatRules` a game uses. Stored with the board so bots and clients agree on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CombatMode {
+    #[default]
+    Sum,
+    Classic {
+        continued: bool,
+    },
+}
+
+impl CombatMode {
+    // `sum`, `classic` or `classic:blitz`
+    pub fn from_name(name: &str) -> Option<CombatMode> {
+        match name {
+            "sum" => Some(CombatMode::Sum),
+            "classic" => Some(CombatMode::Classic { continued: false }),
+            "classic:blitz" => Some(CombatMode::Classic { continued: true }),
+            _ => None,
+        }
+    }
+
+    pub fn with_dice(&self, dice: DiceRules) -> Box<dyn CombatRules> {
+        match self {
+            CombatMode::Sum => Box::new(SumCombat { dice }),
+            CombatMode::Classic { continued } => Box::new(ClassicCombat {
+                continued: *continued,
+                dice,
+            }),
+        }
+    }
+}
+
+// Sum combat. A tie is rerolled as a whole when the policy says so.
+pub fn sum_battle<R: Rng + ?Sized>(
+    attacker_dice: u32,
+    defender_dice: u32,
+    dice: &DiceRules,
+    rng: &mut R,
+) -> bool {
+    let num_attack_rolls: u32 = dice.num_rolled(attacker_dice);
+    let num_defense_rolls: u32 = dice.num_rolled(defender_dice);
+    if num_attack_rolls == 0 {
+        return false;
+    }
+
+    loop {
+        let attacker_sum: u32 = (0..num_attack_rolls).map(|_cur_die| dice.roll(rng)).sum();
+        let defender_sum: u32 = (0..num_defense_rolls).map(|_cur_die| dice.roll(rng)).sum();
+
+        if attacker_sum != defender_sum {
+            return attacker_sum > defender_sum;
+        }
+
+        match dice.ties {
+            TiePolicy::Defender => return false,
+            TiePolicy::Attacker => return true,
+            TiePolicy::Reroll => {}
+        }
+    }
+}
+
+fn classic_capture_probability(
+    attacker_dice: u32,
+    defender_dice: u32,
+    continued: bool,
+    dice: &DiceRules,
+    memo: &mut HashMap<(u32, u32), f64>,
+    rounds: &mut RoundTables,
+) -> f64 {
+    if defender_dice == 0 {
+        return 1.0;
+    }
+    if attacker_dice < 2 {
+        return 0.0;
+    }
+    if let Some(probability) = memo.get(&(attacker_dice, defender_dice)) {
+        return *probability;
+    }
+
+    let num_attack_rolls: u32 = (attacker_dice - 1).min(CLASSIC_ATTACK_DICE);
+    let num_defense_rolls: u32 = defender_dice.min(CLASSIC_DEFENSE_DICE);
+    let num_compared: u32 = num_attack_rolls.min(num_defense_rolls);
+
+    let outcomes: Vec<f64> = rounds
+        .entry((num_attack_rolls, num_defense_rolls))
+        .or_insert_with(|| round_outcomes(num_attack_rolls, num_defense_rolls, dice))
+        .clone();
+
+    let mut probability: f64 = 0.0;
+    for (defender_losses, cur_probability) in outcomes.iter().enumerate() {
+        let defender_left: u32 = defender_dice - defender_losses as u32;
+        let attacker_left: u32 = attacker_dice - (num_compared - defender_losses as u32);
+
+        if defender_left == 0 {
+            probability += cur_probability;
+        } else if continued {
+            probability += cur_probability
+                * classic_capture_probability(
+                    attacker_left,
+                    defender_left,
+                    continued,
+                    dice,
+                    memo,
+                    rounds,
+                );
+        }
+    }
+
+    memo.insert((attacker_dice, defender_dice), probability);
+    probability
+}
+
+// Probability of each number of defender losses in one round. Each side's
+// compared dice are enumerated on their own, then every pair is scored.
+fn round_outcomes(num_attack_rolls: u32, num_defense_rolls: u32, dice: &DiceRules) -> Vec<f64> {
+    let num_compared: usize = num_attack_rolls.min(num_defense_rolls) as usize;
+    let attack_tops: BTreeMap<Vec<u32>, f64> =
+        top_rolls(num_attack_rolls, num_compared, dice.die_faces);
+    let defense_tops: BTreeMap<Vec<u32>, f64> =
+        top_rolls(num_defense_rolls, num_compared, dice.die_faces);
+    let mut outcomes: Vec<f64> = vec![0.0; num_compared + 1];
+
+    for ((attack_rolls, attack_probability), (defense_rolls, defense_probability)) in attack_tops
+        .iter()
+        .flat_map(|attack| defense_tops.iter().map(move |defense| (attack, defense)))
+    {
+        let mut num_wins: usize = 0;
+        let mut num_ties: usize = 0;
+        for (cur_attack, cur_defense) in attack_rolls.iter().zip(defense_rolls) {
+            if cur_attack > cur_defense {
+                num_wins += 1;
+            } else if cur_attack == cur_defense {
+                num_ties += 1;
+            }
+        }
+
+        let combination_probability: f64 = attack_probability * defense_probability;
+        match dice.ties {
+            TiePolicy::Defender => outcomes[num_wins] += combination_probability,
+            TiePolicy::Attacker => outcomes[num_wins + num_ties] += combination_probability,
+            // Both sides reroll the same die, so each rerolled tie is a coin flip
+            TiePolicy::Reroll => {
+                for cur_tie_wins in 0..=num_ties {
+                    outcomes[num_wins + cur_tie_wins] += combination_probability
+                        * binomial(num_ties, cur_tie_wins)
+                        / 2.0_f64.powi(num_ties as i32);
+                }
+            }
+        }
+    }
+
+    outcomes
+}
+
+// Distribution of the highest `num_kept` of `num_rolls` dice, highest first
+fn top_rolls(num_rolls: u32, num_kept: usize, die_faces: u32) -> BTreeMap<Vec<u32>, f64> {
+    let num_combinations: u32 = die_faces.pow(num_rolls);
+    let mut tops: BTreeMap<Vec<u32>, f64> = BTreeMap::new();
+
+    for cur_combination in 0..num_combinations {
+        let mut rolls: Vec<u32> = (0..num_rolls)
+            .map(|cur_die| cur_combination / die_faces.pow(cur_die) % die_faces + 1)
+            .collect();
+        rolls.sort_unstable_by(|first, second| second.cmp(first));
+        rolls.truncate(num_kept);
+        *tops.entry(rolls).or_insert(0.0) += 1.0 / f64::from(num_combinations);
+    }
+
+    tops
+}
+
+fn binomial(n: usize, k: usize) -> f64 {
+    (0..k).fold(1.0, |product, cur_index| {
+        product * (n - cur_index) as f64 / (cur_index + 1) as f64
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn assert_close(expected: f64, actual: f64) {
+        assert!(
+            (expected - actual).abs() < 1e-9,
+            "expected {} but got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn combat_mode_from_name() {
+        assert_eq!(Some(CombatMode::Sum), CombatMode::from_name("sum"));
+        assert_eq!(
+            Some(CombatMode::Classic { continued: true }),
+            CombatMode::from_name("classic:blitz")
+        );
+        assert_eq!(None, CombatMode::from_name("chess"));
+        assert_eq!(
+            "classic",
+            CombatMode::from_name("classic")
+                .unwrap()
+                .with_dice(DiceRules::default())
+                .name()
+        );
+    }
+
+    #[test]
+    fn classic_round_odds() {
+        let classic = ClassicCombat {
+            continued: false,
+            dice: DiceRules::default(),
+        };
+
+        // One die each: 15 of the 36 rolls have the attacker ahead
+        assert_close(15.0 / 36.0, classic.success_probability(2, 1));
+        // Three against two, the attacker takes both defenders in 2890 of 7776 rolls
+        assert_close(2890.0 / 7776.0, classic.success_probability(4, 2));
+        // A single round can never take three defending dice
+        assert_close(0.0, classic.success_probability(8, 3));
+    }
+
+    #[test]
+    fn blitz_is_at_least_as_likely_to_capture() {
+        let single = ClassicCombat {
+            continued: false,
+            dice: DiceRules::default(),
+        };
+        let blitz = ClassicCombat {
+            continued: true,
+            dice: DiceRules::default(),
+        };
+
+        for cur_attacker in 2..=8 {
+            for cur_defender in 1..=8 {
+                let blitz_odds: f64 = blitz.success_probability(cur_attacker, cur_defender);
+                assert!(blitz_odds >= single.success_probability(cur_attacker, cur_defender));
+                assert!((0.0..=1.0).contains(&blitz_odds));
+            }
+        }
+    }
+
+    #[test]
+    fn tie_policies_change_classic_odds() {
+        let odds_with = |ties: TiePolicy| -> f64 {
+            let classic = ClassicCombat {
+                continued: false,
+                dice: DiceRules {
+                    ties,
+                    ..DiceRules::default()
+                },
+            };
+            classic.success_probability(2, 1)
+        };
+
+        // One die each: 15 wins and 6 ties out of 36
+        assert_close(15.0 / 36.0, odds_with(TiePolicy::Defender));
+        assert_close(21.0 / 36.0, odds_with(TiePolicy::Attacker));
+        assert_close(0.5, odds_with(TiePolicy::Reroll));
+    }
+
+    #[test]
+    fn simulated_odds_match_computed_odds() {
+        let dice = DiceRules {
+            die_faces: 4,
+            ties: TiePolicy::Reroll,
+            leave_one_behind: false,
+        };
+        let mut rng = StdRng::seed_from_u64(6);
+
+        for cur_combat in [
+            CombatMode::Sum.with_dice(dice),
+            CombatMode::Classic { continued: true }.with_dice(dice),
+        ] {
+            let num_captures: usize = (0..20_000)
+                .filter(|_cur_battle| cur_combat.resolve(4, 3, &mut rng).captured)
+                .count();
+            let simulated: f64 = num_captures as f64 / 20_000.0;
+
+            assert!((simulated - cur_combat.success_probability(4, 3)).abs() < 0.02);
+        }
+    }
+
+    #[test]
+    fn dice_need_two_faces() {
+        let dice = DiceRules {
+            die_faces: 1,
+            ..DiceRules::default()
+        };
+
+        assert!(dice.validate().is_err());
+        assert!(DiceRules::default().validate().is_ok());
+    }
+
+    #[test]
+    fn dice_faces_are_capped() {
+        let d20 = DiceRules {
+            die_faces: MAX_DIE_FACES,
+            ..DiceRules::default()
+        };
+        let d100 = DiceRules {
+            die_faces: 100,
+            ..DiceRules::default()
+        };
+
+        assert!(d20.validate().is_ok());
+        assert!(d100.validate().is_err());
+    }
+
+    #[test]
+    fn classic_odds_with_large_dice() {
+        let classic = ClassicCombat {
+            continued: false,
+            dice: DiceRules {
+                die_faces: MAX_DIE_FACES,
+                ..DiceRules::default()
+            },
+        };
+
+        // One d20 each: the attacker is ahead in 190 of the 400 rolls
+        assert_close(190.0 / 400.0, classic.success_probability(2, 1));
+        let three_against_two: f64 = classic.success_probability(4, 2);
+        assert!(three_against_two > 0.0 && three_against_two < 1.0);
+    }
+
+    #[test]
+    fn classic_round_removes_one_die_per_comparison() {
+        let classic = ClassicCombat {
+            continued: false,
+            dice: DiceRules::default(),
+        };
+        let mut rng = StdRng::seed_from_u64(4);
+
+        for _cur_battle in 0..100 {
+            let outcome: BattleOutcome = classic.resolve(5, 4, &mut rng);
+            assert_eq!(2, (5 - outcome.attacker_dice) + (4 - outcome.defender_dice));
+            assert!(!outcome.captured);
+        }
+    }
+
+    #[test]
+    fn blitz_runs_until_one_side_is_out() {
+        let blitz = ClassicCombat {
+            continued: true,
+            dice: DiceRules::default(),
+        };
+        let mut rng = StdRng::seed_from_u64(5);
+
+        for _cur_battle in 0..100 {
+            let outcome: BattleOutcome = blitz.resolve(6, 3, &mut rng);
+            assert!(outcome.attacker_dice == 1 || outcome.captured);
+            assert_eq!(outcome.captured, outcome.defender_dice == 0);
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+/// Non-aggression pact. Neither player can attack the other until `expires_on_turn`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pact {
+    pub first_player_id: u32,
+    pub second_player_id: u32,
+    pub expires_on_turn: u32,
+}
+
+impl Pact {
+    pub fn involves(&self, player_id: u32) -> bool {
+        self.first_player_id == player_id || self.second_player_id == player_id
+    }
+
+    pub fn between(&self, first_player_id: u32, second_player_id: u32) -> bool {
+        self.involves(first_player_id) && self.involves(second_player_id)
+    }
+}
+
+/// Pact offered by `proposer` that `partner` has not accepted yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PactProposal {
+    pub proposer: u32,
+    pub partner: u32,
+    /// Rounds the pact lasts once accepted
+    pub turns: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EventKind {
+    PactProposed {
+        proposer: u32,
+        partner: u32,
+        turns: u32,
+    },
+    PactAccepted {
+        proposer: u32,
+        partner: u32,
+        expires_on_turn: u32,
+    },
+    /// A pact ended early by `breaker`
+    PactBroken { breaker: u32, partner: u32 },
+    PactExpired {
+        first_player_id: u32,
+        second_player_id: u32,
+    },
+}
+
+/// Entry of the game's event log, kept with the board so saved games have it too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameEvent {
+    pub turn_number: u32,
+    pub kind: EventKind,
+}
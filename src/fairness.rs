@@ -0,0 +1,173 @@
+use crate::gameboard::Gameboard;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+// Weights of the penalties against the largest region share
+const EXPOSURE_WEIGHT: f64 = 0.5;
+const PRESSURE_WEIGHT: f64 = 0.5;
+
+/// How strong a player's starting position is. `score` is higher for better positions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PositionScore {
+    pub player_id: u32,
+    pub largest_region: u32,
+    /// Share of the player's territories that border an opponent
+    pub border_exposure: f64,
+    /// Opponent dice next to the player's borders, per die the player owns
+    pub dice_pressure: f64,
+    pub score: f64,
+}
+
+pub fn score_positions(gameboard: &Gameboard) -> Vec<PositionScore> {
+    (0..gameboard.num_players)
+        .map(|cur_player_id| score_position(gameboard, cur_player_id))
+        .collect()
+}
+
+pub fn score_position(gameboard: &Gameboard, player_id: u32) -> PositionScore {
+    let mut num_owned: u32 = 0;
+    let mut num_exposed: u32 = 0;
+    let mut own_dice: u32 = 0;
+    let mut threatening_terr_ids: HashSet<u32> = HashSet::new();
+
+    for cur_terr in gameboard.territory_map.values() {
+        if cur_terr.owner_id != player_id {
+            continue;
+        }
+
+        num_owned += 1;
+        own_dice += cur_terr.num_dice;
+
+        let mut is_exposed: bool = false;
+        for cur_neighbor in &cur_terr.neighbors {
+            if let Some(neighbor_terr) = gameboard.territory_map.get(cur_neighbor) {
+                if neighbor_terr.owner_id != player_id {
+                    is_exposed = true;
+                    threatening_terr_ids.insert(neighbor_terr.id);
+                }
+            }
+        }
+
+        if is_exposed {
+            num_exposed += 1;
+        }
+    }
+
+    let largest_region: u32 = gameboard.largest_region(player_id);
+    if num_owned == 0 {
+        return PositionScore {
+            player_id,
+            largest_region,
+            border_exposure: 0.0,
+            dice_pressure: 0.0,
+            score: 0.0,
+        };
+    }
+
+    let enemy_dice: u32 = threatening_terr_ids
+        .iter()
+        .map(|terr_id| gameboard.territory_map[terr_id].num_dice)
+        .sum();
+
+    let region_share: f64 = f64::from(largest_region) / f64::from(num_owned);
+    let border_exposure: f64 = f64::from(num_exposed) / f64::from(num_owned);
+    let dice_pressure: f64 = f64::from(enemy_dice) / f64::from(own_dice.max(1));
+
+    // Squash the pressure into [0, 1) so no single term dominates
+    let score: f64 = region_share
+        - EXPOSURE_WEIGHT * border_exposure
+        - PRESSURE_WEIGHT * dice_pressure / (1.0 + dice_pressure);
+
+    PositionScore {
+        player_id,
+        largest_region,
+        border_exposure,
+        dice_pressure,
+        score,
+    }
+}
+
+// Difference between the best and worst starting position
+pub fn score_spread(scores: &[PositionScore]) -> f64 {
+    let best: f64 = scores
+        .iter()
+        .map(|cur_score| cur_score.score)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let worst: f64 = scores
+        .iter()
+        .map(|cur_score| cur_score.score)
+        .fold(f64::INFINITY, f64::min);
+
+    if scores.is_empty() {
+        0.0
+    } else {
+        best - worst
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameboard::Territory;
+    use std::collections::HashMap;
+
+    // 0 - 1 - 2 - 3, player 0 owns the ends, player 1 the middle
+    fn four_in_a_row() -> Gameboard {
+        let mut territory_map: HashMap<u32, Territory> = HashMap::new();
+        let owners: [u32; 4] = [0, 1, 1, 0];
+        let dice: [u32; 4] = [2, 3, 1, 2];
+
+        for cur_id in 0..4 {
+            let mut neighbors: Vec<u32> = Vec::new();
+            if cur_id > 0 {
+                neighbors.push(cur_id - 1);
+            }
+            if cur_id < 3 {
+                neighbors.push(cur_id + 1);
+            }
+
+            territory_map.insert(
+                cur_id,
+                Territory {
+                    id: cur_id,
+                    num_dice: dice[cur_id as usize],
+                    owner_id: owners[cur_id as usize],
+                    neighbors,
+                    ..Default::default()
+                },
+            );
+        }
+
+        Gameboard::from_territory_map(territory_map, 2)
+    }
+
+    #[test]
+    fn split_player_scores_worse() {
+        let gameboard: Gameboard = four_in_a_row();
+
+        let scores: Vec<PositionScore> = score_positions(&gameboard);
+
+        assert_eq!(1, scores[0].largest_region);
+        assert_eq!(2, scores[1].largest_region);
+        assert!((scores[0].border_exposure - 1.0).abs() < 1e-9);
+        assert!((scores[1].border_exposure - 1.0).abs() < 1e-9);
+        // Player 0 faces 4 dice with 4, player 1 faces 4 dice with 4
+        assert!((scores[0].dice_pressure - 1.0).abs() < 1e-9);
+        assert!((scores[1].dice_pressure - 1.0).abs() < 1e-9);
+        assert!(scores[0].score < scores[1].score);
+        assert!((score_spread(&scores) - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn eliminated_player_scores_zero() {
+        let mut gameboard: Gameboard = four_in_a_row();
+        for cur_terr in gameboard.territory_map.values_mut() {
+            cur_terr.owner_id = 1;
+        }
+
+        let score: PositionScore = score_position(&gameboard, 0);
+
+        assert_eq!(0, score.largest_region);
+        assert_eq!(0.0, score.score);
+    }
+}
@@ -1,21 +1,314 @@
+use crate::combat::{self, BattleOutcome, DiceRules};
+use crate::diplomacy::{EventKind, GameEvent, Pact, PactProposal};
+use crate::fairness::{self, PositionScore};
+use crate::hexmap::{self, HexCell, HexGrid};
+use crate::names;
+use crate::planar::{self, Cell, Point};
+use crate::rules::{CapitalLoss, DiplomacyRules, FogOfWar, FortifyPath, ReinforcementMode, Rules};
+use crate::topology::{self, TopologyOptions};
 use rand::prelude::SliceRandom;
+use rand::rngs::StdRng;
 use rand::thread_rng;
-use rand::Rng;
-use serde::Serialize;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::collections::VecDeque;
 
-#[derive(Debug, Clone, Serialize)]
+pub const DEFAULT_MAX_DICE: u32 = 8;
+/// Owner of territories that belong to no player. Neutrals never attack or reinforce.
+pub const NEUTRAL_OWNER: u32 = u32::MAX;
+
+// Re-deals tried when balancing starting positions before settling for the fairest one
+const MAX_DEALS: u32 = 500;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Territory {
     pub id: u32,
     pub num_dice: u32,
     pub owner_id: u32,
     pub neighbors: Vec<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Id of the `Continent` this territory belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub continent: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub centroid: Option<Point>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub polygon: Vec<Point>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hex_cells: Vec<HexCell>,
+    /// Capitals stay capitals when captured, see `CapitalRules`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub capital: bool,
+    /// Player who founded this capital, which stays the same when it is captured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capital_of: Option<u32>,
+    /// Set in fog of war views on what the player cannot see, see `Gameboard::view_for`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hidden: bool,
+}
+
+/// A group of territories. Owning all of them grants `bonus` extra dice each turn.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Continent {
+    pub id: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub bonus: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MapStyle {
+    /// A random graph shaped by `TopologyOptions`
+    #[default]
+    Graph,
+    /// Territories are Voronoi cells of random points, neighbors share a border
+    Planar,
+    /// Territories are contiguous blobs of hex cells, neighbors share a hex edge
+    Hex { cells_per_territory: u32 },
+}
+
+pub const DEFAULT_CELLS_PER_TERRITORY: u32 = 8;
+
+impl MapStyle {
+    // `hex` takes an optional cell count, e.g. `hex:12`
+    pub fn from_name(name: &str) -> Option<MapStyle> {
+        match name.split_once(':') {
+            None => match name {
+                "graph" => Some(MapStyle::Graph),
+                "planar" => Some(MapStyle::Planar),
+                "hex" => Some(MapStyle::Hex {
+                    cells_per_territory: DEFAULT_CELLS_PER_TERRITORY,
+                }),
+                _ => None,
+            },
+            Some(("hex", cells)) => match cells.parse() {
+                Ok(cells_per_territory) if cells_per_territory > 0 => Some(MapStyle::Hex {
+                    cells_per_territory,
+                }),
+                _ => None,
+            },
+            Some(_) => None,
+        }
+    }
+}
+
+/// Per-player overrides of the starting territories and dice.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Handicap {
+    pub num_territories: Option<u32>,
+    pub num_dice: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardConfig {
+    pub num_players: u32,
+    pub num_territories_per_player: u32,
+    pub num_dice_per_player: u32,
+    pub handicaps: HashMap<u32, Handicap>,
+    /// Extra starting dice for the last seat, which moves last
+    pub last_seat_bonus_dice: u32,
+    /// Share of the whole board, in `[0, 1)`, left neutral
+    pub neutral_share: f64,
+    /// Dice on each neutral territory
+    pub neutral_dice: u32,
+    pub map_style: MapStyle,
+    pub seed: Option<u64>,
+    pub topology: TopologyOptions,
+    /// Re-deal until the starting position scores are at most this far apart
+    pub fairness_tolerance: Option<f64>,
+    /// Give territories and continents generated place names
+    pub name_territories: bool,
+    /// Most dice a territory can hold, also while dealing the starting dice
+    pub max_dice: u32,
+}
+
+impl BoardConfig {
+    pub fn new(
+        num_players: u32,
+        num_territories_per_player: u32,
+        num_dice_per_player: u32,
+    ) -> BoardConfig {
+        BoardConfig {
+            num_players,
+            num_territories_per_player,
+            num_dice_per_player,
+            handicaps: HashMap::new(),
+            last_seat_bonus_dice: 0,
+            neutral_share: 0.0,
+            neutral_dice: 1,
+            map_style: MapStyle::default(),
+            seed: None,
+            topology: TopologyOptions::default(),
+            fairness_tolerance: None,
+            name_territories: false,
+            max_dice: DEFAULT_MAX_DICE,
+        }
+    }
+
+    pub fn num_territories_for(&self, player_id: u32) -> u32 {
+        self.handicaps
+            .get(&player_id)
+            .and_then(|handicap| handicap.num_territories)
+            .unwrap_or(self.num_territories_per_player)
+    }
+
+    pub fn num_dice_for(&self, player_id: u32) -> u32 {
+        let num_dice: u32 = self
+            .handicaps
+            .get(&player_id)
+            .and_then(|handicap| handicap.num_dice)
+            .unwrap_or(self.num_dice_per_player);
+
+        if player_id + 1 == self.num_players {
+            num_dice + self.last_seat_bonus_dice
+        } else {
+            num_dice
+        }
+    }
+
+    pub fn num_neutral_territories(&self) -> u32 {
+        let num_player_territories: u32 = (0..self.num_players)
+            .map(|cur_player_id| self.num_territories_for(cur_player_id))
+            .sum();
+
+        (f64::from(num_player_territories) * self.neutral_share / (1.0 - self.neutral_share))
+            .round() as u32
+    }
+
+    pub fn num_territories(&self) -> u32 {
+        let num_player_territories: u32 = (0..self.num_players)
+            .map(|cur_player_id| self.num_territories_for(cur_player_id))
+            .sum();
+
+        num_player_territories + self.num_neutral_territories()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.num_players == 0 {
+            return Err("A board needs at least one player".to_owned());
+        }
+
+        if let Some(player_id) = self
+            .handicaps
+            .keys()
+            .find(|player_id| **player_id >= self.num_players)
+        {
+            return Err(format!("Handicap for unknown player {}", player_id));
+        }
+
+        for cur_player_id in 0..self.num_players {
+            let num_territories: u32 = self.num_territories_for(cur_player_id);
+            let num_dice: u32 = self.num_dice_for(cur_player_id);
+
+            if num_territories == 0 {
+                return Err(format!(
+                    "Player {} needs at least one territory",
+                    cur_player_id
+                ));
+            }
+            if num_dice < num_territories {
+                return Err(format!(
+                    "Player {} needs at least one die per territory ({} dice for {} territories)",
+                    cur_player_id, num_dice, num_territories
+                ));
+            }
+            if num_dice > num_territories * self.max_dice {
+                return Err(format!(
+                    "Player {} cannot fit {} dice on {} territories",
+                    cur_player_id, num_dice, num_territories
+                ));
+            }
+        }
+
+        if !(0.0..1.0).contains(&self.neutral_share) {
+            return Err("The neutral share must be at least 0 and below 1".to_owned());
+        }
+
+        if self.neutral_dice == 0 || self.neutral_dice > self.max_dice {
+            return Err(format!(
+                "Neutral territories need between 1 and {} dice",
+                self.max_dice
+            ));
+        }
+
+        if self.map_style != MapStyle::Graph && self.topology != TopologyOptions::default() {
+            return Err("Topology options only apply to graph maps".to_owned());
+        }
+
+        if self
+            .fairness_tolerance
+            .is_some_and(|tolerance| tolerance.is_nan() || tolerance < 0.0)
+        {
+            return Err("The fairness tolerance cannot be negative".to_owned());
+        }
+
+        self.topology.validate(self.num_territories())
+    }
+}
+
+/// How a board was generated, saved alongside it so the board can be described or regenerated.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BoardMetadata {
+    pub map_style: MapStyle,
+    pub seed: Option<u64>,
+    pub topology: TopologyOptions,
+    pub fairness_tolerance: Option<f64>,
+    pub position_scores: Vec<PositionScore>,
+    /// Starting territories of each player, by player id
+    pub starting_territories: Vec<u32>,
+    /// Starting dice of each player, by player id
+    pub starting_dice: Vec<u32>,
+    pub num_neutral_territories: u32,
+}
+
+/// What the current player is doing. Draft games start each turn in the
+/// reinforce phase, moving dice during the fortify phase ends attacking for the turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Phase {
+    Reinforce,
+    #[default]
+    Attack,
+    Fortify,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Gameboard {
     pub territory_map: HashMap<u32, Territory>,
+    pub num_players: u32,
+    pub current_player_id: u32,
+    pub turn_number: u32,
+    pub max_dice: u32,
+    #[serde(default)]
+    pub phase: Phase,
+    /// Fortify moves made by the current player this turn
+    #[serde(default)]
+    pub moves_this_turn: u32,
+    /// Dice the current player still has to place during the reinforce phase
+    #[serde(default)]
+    pub pending_reinforcements: u32,
+    /// Set by the first attack, move, placement or end of turn. Teams are fixed from then on.
+    #[serde(default)]
+    pub started: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub continents: Vec<Continent>,
+    /// Team of each player, by player id. Without teams every player plays alone.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub teams: Vec<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pacts: Vec<Pact>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub proposals: Vec<PactProposal>,
+    /// Diplomacy in the order it happened
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<GameEvent>,
+    #[serde(default)]
+    pub rules: Rules,
+    #[serde(default)]
+    pub metadata: BoardMetadata,
 }
 
 impl Gameboard {
@@ -24,7 +317,56 @@ impl Gameboard {
         num_territories_per_player: u32,
         num_dice_per_player: u32,
     ) -> Gameboard {
-        let max_territories = num_territories_per_player * num_players;
+        Gameboard::from_config(&BoardConfig::new(
+            num_players,
+            num_territories_per_player,
+            num_dice_per_player,
+        ))
+    }
+
+    pub fn new_seeded(
+        num_players: u32,
+        num_territories_per_player: u32,
+        num_dice_per_player: u32,
+        seed: u64,
+    ) -> Gameboard {
+        let mut config: BoardConfig =
+            BoardConfig::new(num_players, num_territories_per_player, num_dice_per_player);
+        config.seed = Some(seed);
+
+        Gameboard::from_config(&config)
+    }
+
+    pub fn new_hex(
+        num_players: u32,
+        num_territories_per_player: u32,
+        num_dice_per_player: u32,
+        cells_per_territory: u32,
+    ) -> Gameboard {
+        let mut config: BoardConfig =
+            BoardConfig::new(num_players, num_territories_per_player, num_dice_per_player);
+        config.map_style = MapStyle::Hex {
+            cells_per_territory,
+        };
+
+        Gameboard::from_config(&config)
+    }
+
+    // Panics if the config does not pass `BoardConfig::validate`
+    pub fn from_config(config: &BoardConfig) -> Gameboard {
+        if let Err(err) = config.validate() {
+            panic!("Invalid board config: {}", err);
+        }
+
+        match config.seed {
+            Some(seed) => Gameboard::generate(config, &mut StdRng::seed_from_u64(seed)),
+            None => Gameboard::generate(config, &mut thread_rng()),
+        }
+    }
+
+    fn generate<R: Rng + ?Sized>(config: &BoardConfig, rng: &mut R) -> Gameboard {
+        let num_players: u32 = config.num_players;
+        let max_territories: u32 = config.num_territories();
 
         let mut territory_map: HashMap<u32, Territory> = HashMap::new();
 
@@ -34,33 +376,139 @@ impl Gameboard {
                 num_dice: 1,
                 owner_id: 0,
                 neighbors: Vec::new(),
+                ..Default::default()
             };
 
             territory_map.insert(cur_territory.id, cur_territory);
         }
 
-        assign_territories_to_players(&mut territory_map, num_players, num_territories_per_player);
+        assign_territories_to_players(&mut territory_map, config, rng);
 
-        assign_dice_to_territories(
-            &mut territory_map,
-            num_players,
-            num_territories_per_player,
-            num_dice_per_player,
-        );
+        assign_dice_to_territories(&mut territory_map, config, rng);
+
+        let mut continents: Vec<Continent> = Vec::new();
+        match config.map_style {
+            MapStyle::Graph => {
+                let clusters: Vec<Vec<u32>> =
+                    topology::connect_graph(&mut territory_map, &config.topology, rng);
+                if clusters.len() > 1 {
+                    continents = assign_continents(&mut territory_map, &clusters);
+                }
+            }
+            MapStyle::Planar => connect_planar_territories(&mut territory_map, rng),
+            MapStyle::Hex {
+                cells_per_territory,
+            } => connect_hex_territories(&mut territory_map, cells_per_territory, rng),
+        }
+
+        debug_assert!(is_connected(&territory_map) && verify_neighbors(&territory_map));
+
+        if config.name_territories {
+            name_territories(&mut territory_map, &mut continents, rng);
+        }
+
+        let mut gameboard: Gameboard = Gameboard::from_territory_map(territory_map, num_players);
+        gameboard.continents = continents;
+        gameboard.max_dice = config.max_dice;
+        if let Some(tolerance) = config.fairness_tolerance {
+            gameboard.balance_starting_positions(config, tolerance, rng);
+        }
+
+        gameboard.metadata = BoardMetadata {
+            map_style: config.map_style,
+            seed: config.seed,
+            topology: config.topology.clone(),
+            fairness_tolerance: config.fairness_tolerance,
+            position_scores: fairness::score_positions(&gameboard),
+            starting_territories: (0..num_players)
+                .map(|cur_player_id| config.num_territories_for(cur_player_id))
+                .collect(),
+            starting_dice: (0..num_players)
+                .map(|cur_player_id| config.num_dice_for(cur_player_id))
+                .collect(),
+            num_neutral_territories: config.num_neutral_territories(),
+        };
+
+        gameboard
+    }
 
-        connect_territories(&mut territory_map);
+    // Keeps the map and re-deals territories and dice until the position scores
+    // are within `tolerance`, or keeps the fairest deal seen
+    fn balance_starting_positions<R: Rng + ?Sized>(
+        &mut self,
+        config: &BoardConfig,
+        tolerance: f64,
+        rng: &mut R,
+    ) {
+        let mut best_spread: f64 = fairness::score_spread(&fairness::score_positions(self));
+        let mut best_territory_map: HashMap<u32, Territory> = self.territory_map.clone();
+
+        for _cur_deal in 1..MAX_DEALS {
+            if best_spread <= tolerance {
+                break;
+            }
 
-        while !is_connected(&territory_map) || !verify_neighbors(&territory_map) {
-            clear_neighbors(&mut territory_map);
-            connect_territories(&mut territory_map);
+            for cur_terr in self.territory_map.values_mut() {
+                cur_terr.num_dice = 1;
+            }
+            assign_territories_to_players(&mut self.territory_map, config, rng);
+            assign_dice_to_territories(&mut self.territory_map, config, rng);
+
+            let spread: f64 = fairness::score_spread(&fairness::score_positions(self));
+            if spread < best_spread {
+                best_spread = spread;
+                best_territory_map = self.territory_map.clone();
+            }
         }
 
+        self.territory_map = best_territory_map;
+    }
+
+    pub fn from_territory_map(
+        territory_map: HashMap<u32, Territory>,
+        num_players: u32,
+    ) -> Gameboard {
         Gameboard {
-            territory_map: territory_map,
+            territory_map,
+            num_players,
+            current_player_id: 0,
+            turn_number: 1,
+            max_dice: DEFAULT_MAX_DICE,
+            phase: Phase::Attack,
+            moves_this_turn: 0,
+            pending_reinforcements: 0,
+            started: false,
+            continents: Vec::new(),
+            teams: Vec::new(),
+            pacts: Vec::new(),
+            proposals: Vec::new(),
+            events: Vec::new(),
+            rules: Rules::default(),
+            metadata: BoardMetadata::default(),
         }
     }
 
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn from_json(json: &str) -> Result<Gameboard, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
     pub fn can_attack(&self, attack_from: u32, attack_to: u32) -> bool {
+        match self.phase {
+            Phase::Reinforce => {
+                println!("Cannot attack: Reinforcements have to be placed first");
+                return false;
+            }
+            Phase::Fortify => {
+                println!("Cannot attack: Attacking is over once dice have been moved");
+                return false;
+            }
+            Phase::Attack => {}
+        }
+
         let mut result: bool = false;
         if self.territory_map.contains_key(&attack_from)
             && self.territory_map.contains_key(&attack_to)
@@ -87,69 +535,1002 @@ impl Gameboard {
                 result = false;
                 println!("Cannot attack: Attacking territory has less than 1 die")
             }
+            if !result {
+                return false;
+            }
+
+            if attacker.owner_id == NEUTRAL_OWNER {
+                println!("Cannot attack: Neutral territories do not attack");
+                return false;
+            }
+            if self.are_teammates(attacker.owner_id, attacked.owner_id) {
+                println!("Cannot attack: Selected territories belong to teammates");
+                return false;
+            }
+            if self.has_pact(attacker.owner_id, attacked.owner_id) {
+                println!("Cannot attack: The players have a non-aggression pact");
+                return false;
+            }
         }
 
-        return result;
+        result
     }
 
     pub fn attack(&mut self, attack_from: u32, attack_to: u32) {
+        self.attack_with_rng(attack_from, attack_to, &mut thread_rng());
+    }
+
+    pub fn attack_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        attack_from: u32,
+        attack_to: u32,
+        rng: &mut R,
+    ) {
+        self.attack_and_advance_with_rng(attack_from, attack_to, None, rng);
+    }
+
+    // `advance` is how many dice the attacker would like to move in on a
+    // capture, see `CaptureRule::num_advancing`
+    pub fn attack_and_advance(&mut self, attack_from: u32, attack_to: u32, advance: Option<u32>) {
+        self.attack_and_advance_with_rng(attack_from, attack_to, advance, &mut thread_rng());
+    }
+
+    pub fn attack_and_advance_with_rng<R: Rng + ?Sized>(
+        &mut self,
+        attack_from: u32,
+        attack_to: u32,
+        advance: Option<u32>,
+        rng: &mut R,
+    ) {
+        self.started = true;
         let attacker: &Territory = self.territory_map.get(&attack_from).unwrap();
         let defender: &Territory = self.territory_map.get(&attack_to).unwrap();
 
-        let attack_dice: u32 = attacker.num_dice - 1;
         let attacker_owner: u32 = attacker.owner_id;
+        let defender_owner: u32 = defender.owner_id;
+        let defense_bonus: u32 = self.defense_bonus(attack_to);
+        let mut rng: &mut R = rng;
+        let mut outcome: BattleOutcome = self.rules.combat_rules().resolve(
+            attacker.num_dice,
+            defender.num_dice + defense_bonus,
+            &mut rng,
+        );
+        // Bonus dice are lost first and never stay on the board
+        outcome.defender_dice = outcome.defender_dice.saturating_sub(defense_bonus);
+        if !outcome.captured {
+            outcome.defender_dice = outcome.defender_dice.max(1);
+        }
 
-        if battle(attacker.num_dice, defender.num_dice) {
+        if outcome.captured {
+            let num_advancing: u32 = self
+                .rules
+                .capture
+                .num_advancing(outcome.attacker_dice - 1, advance);
             {
-                let mut attacker: &mut Territory =
-                    self.territory_map.get_mut(&attack_from).unwrap();
-                attacker.num_dice = 1;
+                let attacker: &mut Territory = self.territory_map.get_mut(&attack_from).unwrap();
+                attacker.num_dice = outcome.attacker_dice - num_advancing;
             }
             {
-                let mut defender: &mut Territory = self.territory_map.get_mut(&attack_to).unwrap();
-                defender.num_dice += attack_dice;
+                let defender: &mut Territory = self.territory_map.get_mut(&attack_to).unwrap();
+                defender.num_dice = outcome.defender_dice + num_advancing;
                 defender.owner_id = attacker_owner;
             }
+
+            if self.territory_map[&attack_to].capital {
+                self.capital_taken(defender_owner, attacker_owner);
+            }
+        } else {
+            {
+                let attacker: &mut Territory = self.territory_map.get_mut(&attack_from).unwrap();
+                attacker.num_dice = outcome.attacker_dice;
+            }
+            {
+                let defender: &mut Territory = self.territory_map.get_mut(&attack_to).unwrap();
+                defender.num_dice = outcome.defender_dice;
+            }
+        }
+    }
+
+    // Extra dice `terr_id` defends with
+    pub fn defense_bonus(&self, terr_id: u32) -> u32 {
+        match self.rules.capitals {
+            Some(capitals) if self.territory_map[&terr_id].capital => capitals.defense_bonus,
+            _ => 0,
+        }
+    }
+
+    fn capital_taken(&mut self, loser_id: u32, captor_id: u32) {
+        let capitals = match self.rules.capitals {
+            Some(capitals) => capitals,
+            None => return,
+        };
+        if loser_id == NEUTRAL_OWNER || self.holds_capital(loser_id) {
+            return;
+        }
+
+        println!("Player {} has lost their last capital", loser_id);
+        if capitals.on_loss == CapitalLoss::Transfer {
+            for cur_terr in self.territory_map.values_mut() {
+                if cur_terr.owner_id == loser_id {
+                    cur_terr.owner_id = captor_id;
+                }
+            }
+        }
+    }
+
+    pub fn holds_capital(&self, player_id: u32) -> bool {
+        self.territory_map
+            .values()
+            .any(|cur_terr| cur_terr.capital && cur_terr.owner_id == player_id)
+    }
+
+    // Players without a capital sit out the rest of the game once capitals are in play
+    pub fn is_eliminated(&self, player_id: u32) -> bool {
+        self.rules.capitals.is_some()
+            && self.territory_map.values().any(|cur_terr| cur_terr.capital)
+            && !self.holds_capital(player_id)
+    }
+
+    // Makes one random territory of every player without a capital their capital
+    pub fn assign_capitals_with_rng<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        for cur_player_id in 0..self.num_players {
+            if self.holds_capital(cur_player_id) {
+                continue;
+            }
+
+            let mut owned: Vec<u32> = self
+                .territory_map
+                .values()
+                .filter(|cur_terr| cur_terr.owner_id == cur_player_id)
+                .map(|cur_terr| cur_terr.id)
+                .collect();
+            owned.sort_unstable();
+
+            if let Some(terr_id) = owned.choose(rng) {
+                let capital: &mut Territory = self.territory_map.get_mut(terr_id).unwrap();
+                capital.capital = true;
+                capital.capital_of = Some(cur_player_id);
+            }
+        }
+    }
+
+    // Players may move their capital during the first round
+    pub fn can_designate_capital(&self, terr_id: u32) -> bool {
+        if self.rules.capitals.is_none() {
+            println!("Cannot designate capital: This game has no capitals");
+            return false;
+        }
+        if self.turn_number > self.num_players {
+            println!("Cannot designate capital: Capitals are fixed after the first round");
+            return false;
+        }
+        if !self.is_owned_by(terr_id, self.current_player_id) {
+            println!("Cannot designate capital: Territory belongs to another player");
+            return false;
+        }
+        if self.territory_map[&terr_id].capital
+            && self.founder_of(&self.territory_map[&terr_id]) != self.current_player_id
+        {
+            println!("Cannot designate capital: Territory is another player's captured capital");
+            return false;
+        }
+
+        true
+    }
+
+    // Call `can_designate_capital` first. Only the player's own capital moves,
+    // capitals they captured stay where they are.
+    pub fn designate_capital(&mut self, terr_id: u32) {
+        let player_id: u32 = self.current_player_id;
+        let own_capitals: Vec<u32> = self
+            .territory_map
+            .values()
+            .filter(|cur_terr| cur_terr.capital && self.founder_of(cur_terr) == player_id)
+            .map(|cur_terr| cur_terr.id)
+            .collect();
+
+        for cur_id in own_capitals {
+            let old_capital: &mut Territory = self.territory_map.get_mut(&cur_id).unwrap();
+            old_capital.capital = false;
+            old_capital.capital_of = None;
+        }
+
+        let new_capital: &mut Territory = self.territory_map.get_mut(&terr_id).unwrap();
+        new_capital.capital = true;
+        new_capital.capital_of = Some(player_id);
+    }
+
+    // Boards saved before founders were recorded count a capital as its owner's own
+    fn founder_of(&self, terr: &Territory) -> u32 {
+        terr.capital_of.unwrap_or(terr.owner_id)
+    }
+
+    pub fn legal_attacks(&self, player_id: u32) -> Vec<(u32, u32)> {
+        let mut attacks: Vec<(u32, u32)> = Vec::new();
+        if self.phase != Phase::Attack {
+            return attacks;
+        }
+
+        for cur_terr in self.territory_map.values() {
+            if cur_terr.owner_id != player_id || cur_terr.num_dice <= 1 {
+                continue;
+            }
+
+            for cur_neighbor in &cur_terr.neighbors {
+                if let Some(neighbor_terr) = self.territory_map.get(cur_neighbor) {
+                    if !self.are_teammates(neighbor_terr.owner_id, player_id)
+                        && !self.has_pact(neighbor_terr.owner_id, player_id)
+                    {
+                        attacks.push((cur_terr.id, neighbor_terr.id));
+                    }
+                }
+            }
+        }
+
+        attacks.sort_unstable();
+        attacks
+    }
+
+    // Whether the current player can move `count` dice from `move_from` to `move_to`
+    pub fn can_move(&self, move_from: u32, move_to: u32, count: u32) -> bool {
+        let fortify = match self.rules.fortify {
+            Some(fortify) => fortify,
+            None => {
+                println!("Cannot move: This game has no fortify phase");
+                return false;
+            }
+        };
+        if self.phase == Phase::Reinforce {
+            println!("Cannot move: Reinforcements have to be placed first");
+            return false;
+        }
+
+        let player_id: u32 = self.current_player_id;
+        if !self.is_owned_by(move_from, player_id) || !self.is_owned_by(move_to, player_id) {
+            println!("Cannot move: Both territories must belong to the current player");
+            return false;
+        }
+        if move_from == move_to {
+            println!("Cannot move: Dice must move to another territory");
+            return false;
+        }
+        if self.moves_this_turn >= fortify.max_moves {
+            println!("Cannot move: No moves left this turn");
+            return false;
+        }
+
+        let source: &Territory = &self.territory_map[&move_from];
+        let target: &Territory = &self.territory_map[&move_to];
+        if count == 0 || count >= source.num_dice {
+            println!("Cannot move: One die has to stay behind");
+            return false;
+        }
+        if target.num_dice + count > self.max_dice {
+            println!("Cannot move: Target territory would hold too many dice");
+            return false;
+        }
+
+        let is_reachable: bool = match fortify.path {
+            FortifyPath::Neighbors => source.neighbors.contains(&move_to),
+            FortifyPath::Connected => self.is_connected_through_owner(move_from, move_to),
+        };
+        if !is_reachable {
+            println!("Cannot move: No path between the territories");
+        }
+
+        is_reachable
+    }
+
+    // Call `can_move` first
+    pub fn move_dice(&mut self, move_from: u32, move_to: u32, count: u32) {
+        self.started = true;
+        self.territory_map.get_mut(&move_from).unwrap().num_dice -= count;
+        self.territory_map.get_mut(&move_to).unwrap().num_dice += count;
+        self.phase = Phase::Fortify;
+        self.moves_this_turn += 1;
+    }
+
+    // Whether the current player can place `count` of their pending reinforcements on `terr_id`
+    pub fn can_place(&self, terr_id: u32, count: u32) -> bool {
+        if self.phase != Phase::Reinforce {
+            println!("Cannot place: There are no reinforcements to place");
+            return false;
+        }
+        if !self.is_owned_by(terr_id, self.current_player_id) {
+            println!("Cannot place: Territory belongs to another player");
+            return false;
+        }
+        if count == 0 || count > self.pending_reinforcements {
+            println!(
+                "Cannot place: {} reinforcements are left",
+                self.pending_reinforcements
+            );
+            return false;
+        }
+        if self.territory_map[&terr_id].num_dice + count > self.max_dice {
+            println!("Cannot place: Territory would hold too many dice");
+            return false;
+        }
+
+        true
+    }
+
+    // Call `can_place` first. Attacking starts once every reinforcement is placed.
+    pub fn place_dice(&mut self, terr_id: u32, count: u32) {
+        self.started = true;
+        self.territory_map.get_mut(&terr_id).unwrap().num_dice += count;
+        self.pending_reinforcements -= count;
+        if self.pending_reinforcements == 0 {
+            self.phase = Phase::Attack;
+        }
+    }
+
+    // Places whatever the current player has left to place at random, for bots
+    pub fn place_at_random_with_rng<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        if self.phase != Phase::Reinforce {
+            return;
+        }
+
+        self.reinforce(self.current_player_id, self.pending_reinforcements, rng);
+        self.pending_reinforcements = 0;
+        self.phase = Phase::Attack;
+    }
+
+    // Whether `to` can be reached from `from` only through territories of `from`'s owner
+    fn is_connected_through_owner(&self, from: u32, to: u32) -> bool {
+        let owner_id: u32 = self.territory_map[&from].owner_id;
+        let mut visited: HashSet<u32> = HashSet::new();
+        let mut queue: VecDeque<u32> = VecDeque::new();
+        visited.insert(from);
+        queue.push_back(from);
+
+        while let Some(cur_id) = queue.pop_front() {
+            if cur_id == to {
+                return true;
+            }
+
+            for cur_neighbor in &self.territory_map[&cur_id].neighbors {
+                if self.is_owned_by(*cur_neighbor, owner_id) && visited.insert(*cur_neighbor) {
+                    queue.push_back(*cur_neighbor);
+                }
+            }
+        }
+
+        false
+    }
+
+    pub fn is_owned_by(&self, terr_id: u32, player_id: u32) -> bool {
+        self.territory_map
+            .get(&terr_id)
+            .is_some_and(|terr| terr.owner_id == player_id)
+    }
+
+    pub fn num_territories_owned(&self, player_id: u32) -> u32 {
+        self.territory_map
+            .values()
+            .filter(|cur_terr| cur_terr.owner_id == player_id)
+            .count() as u32
+    }
+
+    // Territories of the player and their teammates together
+    pub fn num_team_territories(&self, player_id: u32) -> u32 {
+        self.territory_map
+            .values()
+            .filter(|cur_terr| self.are_teammates(cur_terr.owner_id, player_id))
+            .count() as u32
+    }
+
+    pub fn largest_region(&self, player_id: u32) -> u32 {
+        self.largest_region_where(|owner_id| owner_id == player_id)
+    }
+
+    // Largest region held by the player and their teammates together
+    pub fn largest_team_region(&self, player_id: u32) -> u32 {
+        self.largest_region_where(|owner_id| self.are_teammates(owner_id, player_id))
+    }
+
+    fn largest_region_where<F: Fn(u32) -> bool>(&self, belongs: F) -> u32 {
+        let mut visited: HashSet<u32> = HashSet::new();
+        let mut largest: u32 = 0;
+
+        for cur_terr in self.territory_map.values() {
+            if !belongs(cur_terr.owner_id) || visited.contains(&cur_terr.id) {
+                continue;
+            }
+
+            let mut region_size: u32 = 0;
+            let mut queue: VecDeque<u32> = VecDeque::new();
+            queue.push_back(cur_terr.id);
+            visited.insert(cur_terr.id);
+
+            while let Some(cur_id) = queue.pop_front() {
+                region_size += 1;
+
+                for cur_neighbor in &self.territory_map[&cur_id].neighbors {
+                    if let Some(neighbor_terr) = self.territory_map.get(cur_neighbor) {
+                        if belongs(neighbor_terr.owner_id) && visited.insert(*cur_neighbor) {
+                            queue.push_back(*cur_neighbor);
+                        }
+                    }
+                }
+            }
+
+            largest = largest.max(region_size);
+        }
+
+        largest
+    }
+
+    pub fn team_of(&self, player_id: u32) -> u32 {
+        self.teams
+            .get(player_id as usize)
+            .copied()
+            .unwrap_or(player_id)
+    }
+
+    // A player is their own teammate, neutral territories are on nobody's team
+    pub fn are_teammates(&self, first_player_id: u32, second_player_id: u32) -> bool {
+        first_player_id != NEUTRAL_OWNER
+            && second_player_id != NEUTRAL_OWNER
+            && self.team_of(first_player_id) == self.team_of(second_player_id)
+    }
+
+    // Teams that own territory. A game needs two of them, or it is won before anyone moves.
+    pub fn num_teams_in_play(&self) -> u32 {
+        self.num_teams_in_play_where(|owner_id| self.team_of(owner_id))
+    }
+
+    fn num_teams_in_play_where<F: Fn(u32) -> u32>(&self, team_of: F) -> u32 {
+        self.territory_map
+            .values()
+            .filter(|cur_terr| cur_terr.owner_id != NEUTRAL_OWNER)
+            .map(|cur_terr| team_of(cur_terr.owner_id))
+            .collect::<HashSet<u32>>()
+            .len() as u32
+    }
+
+    // Teams are numbered like seats, and can be changed until the first turn ends
+    pub fn can_join_team(&self, player_id: u32, team: u32) -> bool {
+        if self.started || self.turn_number > 1 {
+            println!("Cannot join team: Teams are fixed once the game has started");
+            return false;
+        }
+        if player_id >= self.num_players || team >= self.num_players {
+            println!(
+                "Cannot join team: There are only {} teams",
+                self.num_players
+            );
+            return false;
+        }
+        let num_teams_after: u32 = self.num_teams_in_play_where(|owner_id| {
+            if owner_id == player_id {
+                team
+            } else {
+                self.team_of(owner_id)
+            }
+        });
+        if num_teams_after < 2 {
+            println!("Cannot join team: Every player would be on the same team");
+            return false;
+        }
+
+        true
+    }
+
+    // Call `can_join_team` first. Every other player keeps their team.
+    pub fn join_team(&mut self, player_id: u32, team: u32) {
+        if self.teams.is_empty() {
+            self.teams = (0..self.num_players).collect();
+        }
+
+        self.teams[player_id as usize] = team;
+    }
+
+    // Territories of the player's team and their direct neighbors
+    pub fn visible_territories(&self, player_id: u32) -> HashSet<u32> {
+        let mut visible: HashSet<u32> = HashSet::new();
+
+        for cur_terr in self.territory_map.values() {
+            if self.are_teammates(cur_terr.owner_id, player_id) {
+                visible.insert(cur_terr.id);
+                visible.extend(cur_terr.neighbors.iter().copied());
+            }
+        }
+
+        visible
+    }
+
+    pub fn can_see(&self, player_id: u32, terr_id: u32) -> bool {
+        self.rules.fog == FogOfWar::Off || self.visible_territories(player_id).contains(&terr_id)
+    }
+
+    // The board as `player_id` sees it. Hidden dice are 0 and hidden owners neutral.
+    pub fn view_for(&self, player_id: u32) -> Gameboard {
+        let mut view: Gameboard = self.clone();
+        if self.rules.fog == FogOfWar::Off {
+            return view;
+        }
+
+        let visible: HashSet<u32> = self.visible_territories(player_id);
+        for cur_terr in view.territory_map.values_mut() {
+            if visible.contains(&cur_terr.id) {
+                continue;
+            }
+
+            cur_terr.hidden = true;
+            cur_terr.num_dice = 0;
+            if self.rules.fog == FogOfWar::Ownership {
+                cur_terr.owner_id = NEUTRAL_OWNER;
+                cur_terr.capital = false;
+                cur_terr.capital_of = None;
+            }
+        }
+        // The seed would let a client regenerate the starting board
+        view.metadata.seed = None;
+        // The reinforcement count gives away the size of the current player's largest region
+        if player_id != self.current_player_id {
+            view.pending_reinforcements = 0;
+        }
+
+        view
+    }
+
+    pub fn has_pact(&self, first_player_id: u32, second_player_id: u32) -> bool {
+        self.pacts
+            .iter()
+            .any(|cur_pact| cur_pact.between(first_player_id, second_player_id))
+    }
+
+    // Pacts can be offered to any other player still in the game who is not a teammate
+    pub fn can_propose_pact(&self, proposer: u32, partner: u32, turns: u32) -> bool {
+        let diplomacy: DiplomacyRules = match self.rules.diplomacy {
+            Some(diplomacy) => diplomacy,
+            None => {
+                println!("Cannot propose pact: Diplomacy is not enabled");
+                return false;
+            }
+        };
+
+        if partner >= self.num_players || proposer == partner {
+            println!(
+                "Cannot propose pact: Player {} cannot be a partner",
+                partner
+            );
+            return false;
+        }
+        if self.are_teammates(proposer, partner) {
+            println!("Cannot propose pact: Teammates never attack each other");
+            return false;
+        }
+        if self.num_territories_owned(partner) == 0 || self.is_eliminated(partner) {
+            println!("Cannot propose pact: Player {} is out of the game", partner);
+            return false;
+        }
+        if turns == 0 || turns > diplomacy.max_turns {
+            println!(
+                "Cannot propose pact: Pacts last between 1 and {} rounds",
+                diplomacy.max_turns
+            );
+            return false;
+        }
+        if self.has_pact(proposer, partner) {
+            println!("Cannot propose pact: The players already have a pact");
+            return false;
+        }
+
+        true
+    }
+
+    // Call `can_propose_pact` first. Replaces an earlier offer to the same partner.
+    pub fn propose_pact(&mut self, proposer: u32, partner: u32, turns: u32) {
+        self.proposals.retain(|cur_proposal| {
+            cur_proposal.proposer != proposer || cur_proposal.partner != partner
+        });
+        self.proposals.push(PactProposal {
+            proposer,
+            partner,
+            turns,
+        });
+        self.log_event(EventKind::PactProposed {
+            proposer,
+            partner,
+            turns,
+        });
+    }
+
+    pub fn can_accept_pact(&self, partner: u32, proposer: u32) -> bool {
+        if self.rules.diplomacy.is_none() {
+            println!("Cannot accept pact: Diplomacy is not enabled");
+            return false;
+        }
+        if !self.proposals.iter().any(|cur_proposal| {
+            cur_proposal.proposer == proposer && cur_proposal.partner == partner
+        }) {
+            println!(
+                "Cannot accept pact: Player {} has not proposed one to player {}",
+                proposer, partner
+            );
+            return false;
+        }
+        if self.has_pact(proposer, partner) {
+            println!("Cannot accept pact: The players already have a pact");
+            return false;
+        }
+        // The proposal may be several turns old, so the players can have changed since
+        if [proposer, partner].iter().any(|cur_player_id| {
+            self.num_territories_owned(*cur_player_id) == 0 || self.is_eliminated(*cur_player_id)
+        }) {
+            println!("Cannot accept pact: A player is out of the game");
+            return false;
+        }
+        if self.are_teammates(proposer, partner) {
+            println!("Cannot accept pact: The players are teammates");
+            return false;
+        }
+
+        true
+    }
+
+    // Call `can_accept_pact` first. A round is one turn of every player.
+    pub fn accept_pact(&mut self, partner: u32, proposer: u32) {
+        let turns: u32 = self
+            .proposals
+            .iter()
+            .find(|cur_proposal| {
+                cur_proposal.proposer == proposer && cur_proposal.partner == partner
+            })
+            .unwrap()
+            .turns;
+        self.proposals.retain(|cur_proposal| {
+            !(cur_proposal.proposer == proposer && cur_proposal.partner == partner
+                || cur_proposal.proposer == partner && cur_proposal.partner == proposer)
+        });
+
+        let expires_on_turn: u32 = self
+            .turn_number
+            .saturating_add(turns.saturating_mul(self.num_players));
+        self.pacts.push(Pact {
+            first_player_id: proposer,
+            second_player_id: partner,
+            expires_on_turn,
+        });
+        self.log_event(EventKind::PactAccepted {
+            proposer,
+            partner,
+            expires_on_turn,
+        });
+    }
+
+    pub fn can_break_pact(&self, breaker: u32, partner: u32) -> bool {
+        if !self.has_pact(breaker, partner) {
+            println!("Cannot break pact: There is no pact between the players");
+            return false;
+        }
+        if !self
+            .rules
+            .diplomacy
+            .is_some_and(|diplomacy| diplomacy.allow_breaking)
+        {
+            println!("Cannot break pact: Pacts have to run until they expire");
+            return false;
+        }
+
+        true
+    }
+
+    // Call `can_break_pact` first
+    pub fn break_pact(&mut self, breaker: u32, partner: u32) {
+        self.pacts
+            .retain(|cur_pact| !cur_pact.between(breaker, partner));
+        self.log_event(EventKind::PactBroken { breaker, partner });
+    }
+
+    fn expire_pacts(&mut self) {
+        let (expired, active): (Vec<Pact>, Vec<Pact>) = self
+            .pacts
+            .iter()
+            .partition(|cur_pact| cur_pact.expires_on_turn <= self.turn_number);
+        self.pacts = active;
+
+        for cur_pact in expired {
+            self.log_event(EventKind::PactExpired {
+                first_player_id: cur_pact.first_player_id,
+                second_player_id: cur_pact.second_player_id,
+            });
+        }
+    }
+
+    fn log_event(&mut self, kind: EventKind) {
+        self.events.push(GameEvent {
+            turn_number: self.turn_number,
+            kind,
+        });
+    }
+
+    // Sum of the bonuses of every continent the player owns entirely
+    pub fn continent_bonus(&self, player_id: u32) -> u32 {
+        self.continent_bonus_where(|owner_id| owner_id == player_id)
+    }
+
+    fn continent_bonus_where<F: Fn(u32) -> bool>(&self, belongs: F) -> u32 {
+        self.continents
+            .iter()
+            .filter(|cur_continent| {
+                let mut members = self
+                    .territory_map
+                    .values()
+                    .filter(|terr| terr.continent == Some(cur_continent.id))
+                    .peekable();
+                members.peek().is_some() && members.all(|terr| belongs(terr.owner_id))
+            })
+            .map(|cur_continent| cur_continent.bonus)
+            .sum()
+    }
+
+    // Checked after every attack and every turn, so whichever condition is met first ends the game
+    pub fn winner(&self) -> Option<u32> {
+        self.last_player_standing()
+            .or_else(|| self.capital_winner())
+            .or_else(|| self.victory_condition_winner())
+    }
+
+    // Neutral territories do not stand in the way of the last team standing.
+    // A team win is reported as its lowest player id still on the board.
+    fn last_player_standing(&self) -> Option<u32> {
+        let owners: HashSet<u32> = self
+            .territory_map
+            .values()
+            .map(|cur_terr| cur_terr.owner_id)
+            .filter(|cur_owner| *cur_owner != NEUTRAL_OWNER)
+            .collect();
+        let first_owner: u32 = *owners.iter().min()?;
+
+        if owners
+            .iter()
+            .all(|cur_owner| self.are_teammates(*cur_owner, first_owner))
+        {
+            Some(first_owner)
         } else {
+            None
+        }
+    }
+
+    fn capital_winner(&self) -> Option<u32> {
+        if !self
+            .rules
+            .capitals
+            .is_some_and(|capitals| capitals.capital_victory)
+        {
+            return None;
+        }
+
+        let capital_owners: HashSet<u32> = self
+            .territory_map
+            .values()
+            .filter(|cur_terr| cur_terr.capital)
+            .map(|cur_terr| cur_terr.owner_id)
+            .collect();
+        let first_owner: u32 = *capital_owners.iter().min()?;
+
+        if capital_owners
+            .iter()
+            .all(|cur_owner| self.are_teammates(*cur_owner, first_owner))
+        {
+            Some(first_owner)
+        } else {
+            None
+        }
+    }
+
+    fn victory_condition_winner(&self) -> Option<u32> {
+        let victory = self.rules.victory;
+        // `winner` runs after every step of bot games, so skip the standings when no condition is set
+        if victory.turn_limit.is_none()
+            && victory.territory_percent.is_none()
+            && victory.score_limit.is_none()
+        {
+            return None;
+        }
+
+        if let Some(percent) = victory.territory_percent {
+            let num_needed: u64 =
+                (self.territory_map.len() as u64 * u64::from(percent)).div_ceil(100);
+            let leader: u32 = self.leader()?;
+            if u64::from(self.num_team_territories(leader)) >= num_needed {
+                return Some(leader);
+            }
+        }
+
+        if let Some(score_limit) = victory.score_limit {
+            let best_scorer: Option<u32> = self
+                .active_players()
+                .into_iter()
+                .filter(|cur_player_id| self.score(*cur_player_id) >= score_limit)
+                .max_by_key(|cur_player_id| {
+                    (self.score(*cur_player_id), self.standing(*cur_player_id))
+                });
+            if best_scorer.is_some() {
+                return best_scorer;
+            }
+        }
+
+        // A round is one turn of every seat, so each seat gets the same number of turns
+        if victory.turn_limit.is_some_and(|turn_limit| {
+            self.turn_number > turn_limit.saturating_mul(self.num_players)
+        }) {
+            return self.leader();
+        }
+
+        None
+    }
+
+    // A territory is worth one point, owned continents are worth their bonus.
+    // Teammates share their score, and continents they hold together count.
+    pub fn score(&self, player_id: u32) -> u32 {
+        self.num_team_territories(player_id)
+            + self.continent_bonus_where(|owner_id| self.are_teammates(owner_id, player_id))
+    }
+
+    // Players still taking turns
+    fn active_players(&self) -> Vec<u32> {
+        (0..self.num_players)
+            .filter(|cur_player_id| {
+                self.num_territories_owned(*cur_player_id) > 0
+                    && !self.is_eliminated(*cur_player_id)
+            })
+            .collect()
+    }
+
+    // Team territories then team dice, with the lower player id ahead on a full tie.
+    // Teammates stand level, so a team is led by its lowest player id.
+    fn standing(&self, player_id: u32) -> (u32, u32, std::cmp::Reverse<u32>) {
+        let num_dice: u32 = self
+            .territory_map
+            .values()
+            .filter(|cur_terr| self.are_teammates(cur_terr.owner_id, player_id))
+            .map(|cur_terr| cur_terr.num_dice)
+            .sum();
+
+        (
+            self.num_team_territories(player_id),
+            num_dice,
+            std::cmp::Reverse(player_id),
+        )
+    }
+
+    fn leader(&self) -> Option<u32> {
+        self.active_players()
+            .into_iter()
+            .max_by_key(|cur_player_id| self.standing(*cur_player_id))
+    }
+
+    pub fn end_turn(&mut self) {
+        self.end_turn_with_rng(&mut thread_rng());
+    }
+
+    pub fn end_turn_with_rng<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.started = true;
+        self.phase = Phase::Attack;
+        self.moves_this_turn = 0;
+
+        if self.rules.reinforcements == ReinforcementMode::Random {
+            let reinforcements: u32 = self.num_reinforcements(self.current_player_id);
+            self.reinforce(self.current_player_id, reinforcements, rng);
+        }
+
+        if self.winner().is_some() {
+            return;
+        }
+
+        let mut next_player_id: u32 = self.current_player_id;
+        loop {
+            next_player_id = (next_player_id + 1) % self.num_players;
+            if self.num_territories_owned(next_player_id) > 0 && !self.is_eliminated(next_player_id)
             {
-                let mut attacker: &mut Territory =
-                    self.territory_map.get_mut(&attack_from).unwrap();
-                attacker.num_dice = 1;
+                break;
+            }
+        }
+
+        self.current_player_id = next_player_id;
+        self.turn_number += 1;
+        self.expire_pacts();
+
+        if self.rules.reinforcements == ReinforcementMode::Draft {
+            self.start_draft();
+        }
+    }
+
+    pub fn num_reinforcements(&self, player_id: u32) -> u32 {
+        let region: u32 = if self.rules.team_regions {
+            self.largest_team_region(player_id)
+        } else {
+            self.largest_region(player_id)
+        };
+
+        region + self.continent_bonus(player_id)
+    }
+
+    // Reinforcements beyond what the player's territories can hold are lost
+    fn start_draft(&mut self) {
+        let free_space: u32 = self
+            .territory_map
+            .values()
+            .filter(|cur_terr| cur_terr.owner_id == self.current_player_id)
+            .map(|cur_terr| self.max_dice.saturating_sub(cur_terr.num_dice))
+            .sum();
+
+        self.pending_reinforcements = self
+            .num_reinforcements(self.current_player_id)
+            .min(free_space);
+        if self.pending_reinforcements > 0 {
+            self.phase = Phase::Reinforce;
+        }
+    }
+
+    fn reinforce<R: Rng + ?Sized>(&mut self, player_id: u32, num_dice: u32, rng: &mut R) {
+        let max_dice: u32 = self.max_dice;
+
+        for _cur_die in 0..num_dice {
+            let mut open_territories: Vec<u32> = self
+                .territory_map
+                .values()
+                .filter(|cur_terr| cur_terr.owner_id == player_id && cur_terr.num_dice < max_dice)
+                .map(|cur_terr| cur_terr.id)
+                .collect();
+            open_territories.sort_unstable();
+
+            match open_territories.choose(rng) {
+                Some(terr_id) => self.territory_map.get_mut(terr_id).unwrap().num_dice += 1,
+                None => break,
             }
         }
     }
 }
 
-fn assign_territories_to_players(
+// Sets aside the neutral territories, then deals the rest round-robin,
+// skipping players who have their share
+fn assign_territories_to_players<R: Rng + ?Sized>(
     territory_map: &mut HashMap<u32, Territory>,
-    num_players: u32,
-    num_territories_per_player: u32,
+    config: &BoardConfig,
+    rng: &mut R,
 ) {
-    let max_territories = num_territories_per_player * num_players;
+    let mut territory_ids: Vec<u32> = (0..config.num_territories()).collect();
 
-    let mut territory_ids: Vec<u32> = (0..max_territories).collect();
+    territory_ids.shuffle(rng);
 
-    let mut rng = thread_rng();
-    territory_ids.shuffle(&mut rng);
+    let num_neutral: usize = config.num_neutral_territories() as usize;
+    for cur_territory in territory_ids.drain(..num_neutral) {
+        territory_map.get_mut(&cur_territory).unwrap().owner_id = NEUTRAL_OWNER;
+    }
 
+    let mut territories_left: Vec<u32> = (0..config.num_players)
+        .map(|cur_player_id| config.num_territories_for(cur_player_id))
+        .collect();
     let mut cur_player_id: u32 = 0;
     for cur_territory in territory_ids {
-        cur_player_id = cur_player_id % num_players;
-        let mut this_terr: &mut Territory = territory_map.get_mut(&cur_territory).unwrap();
+        while territories_left[cur_player_id as usize] == 0 {
+            cur_player_id = (cur_player_id + 1) % config.num_players;
+        }
+
+        let this_terr: &mut Territory = territory_map.get_mut(&cur_territory).unwrap();
         this_terr.owner_id = cur_player_id;
-        cur_player_id += 1;
+        territories_left[cur_player_id as usize] -= 1;
+        cur_player_id = (cur_player_id + 1) % config.num_players;
     }
 }
 
-fn assign_dice_to_territories(
+fn assign_dice_to_territories<R: Rng + ?Sized>(
     territory_map: &mut HashMap<u32, Territory>,
-    num_players: u32,
-    num_territories_per_player: u32,
-    num_dice_per_player: u32,
+    config: &BoardConfig,
+    rng: &mut R,
 ) {
-    let mut rng = thread_rng();
+    for cur_terr in territory_map.values_mut() {
+        if cur_terr.owner_id == NEUTRAL_OWNER {
+            cur_terr.num_dice = config.neutral_dice;
+        }
+    }
 
-    for cur_player_id in 0..num_players {
+    for cur_player_id in 0..config.num_players {
         let mut assigned_dice: u32 = 0;
         let mut cur_players_territories: Vec<u32> = Vec::new();
 
@@ -158,10 +1539,15 @@ fn assign_dice_to_territories(
                 cur_players_territories.push(cur_terr.id);
             }
         }
-
-        while assigned_dice < (num_dice_per_player - num_territories_per_player) {
-            cur_players_territories.shuffle(&mut rng);
-            let mut this_terr: &mut Territory =
+        cur_players_territories.sort_unstable();
+
+        let extra_dice: u32 =
+            config.num_dice_for(cur_player_id) - config.num_territories_for(cur_player_id);
+        while assigned_dice < extra_dice {
+            cur_players_territories
+                .retain(|terr_id| territory_map[terr_id].num_dice < config.max_dice);
+            cur_players_territories.shuffle(rng);
+            let this_terr: &mut Territory =
                 territory_map.get_mut(&cur_players_territories[0]).unwrap();
             this_terr.num_dice += 1;
             assigned_dice += 1;
@@ -169,36 +1555,141 @@ fn assign_dice_to_territories(
     }
 }
 
-fn connect_territories(territory_map: &mut HashMap<u32, Territory>) {
-    let mut all_terr_ids: Vec<u32> = Vec::new();
-    for cur_terr_id in territory_map.keys() {
-        all_terr_ids.push(*cur_terr_id);
+// Continents are worth about half their territories, like in classic Risk
+fn assign_continents(
+    territory_map: &mut HashMap<u32, Territory>,
+    clusters: &[Vec<u32>],
+) -> Vec<Continent> {
+    let mut continents: Vec<Continent> = Vec::new();
+
+    for (cur_index, cur_cluster) in clusters.iter().enumerate() {
+        let continent_id: u32 = cur_index as u32;
+        for cur_terr_id in cur_cluster {
+            territory_map.get_mut(cur_terr_id).unwrap().continent = Some(continent_id);
+        }
+
+        continents.push(Continent {
+            id: continent_id,
+            name: None,
+            bonus: (cur_cluster.len() as u32 / 2).max(1),
+        });
+    }
+
+    continents
+}
+
+fn name_territories<R: Rng + ?Sized>(
+    territory_map: &mut HashMap<u32, Territory>,
+    continents: &mut [Continent],
+    rng: &mut R,
+) {
+    let mut all_terr_ids: Vec<u32> = territory_map.keys().copied().collect();
+    all_terr_ids.sort_unstable();
+
+    let mut place_names: Vec<String> =
+        names::place_names(all_terr_ids.len() + continents.len(), rng);
+
+    for cur_continent in continents.iter_mut() {
+        cur_continent.name = place_names.pop();
+    }
+    for (cur_terr_id, cur_name) in all_terr_ids.iter().zip(place_names) {
+        territory_map.get_mut(cur_terr_id).unwrap().name = Some(cur_name);
     }
+}
 
-    all_terr_ids.sort();
-    let min: u32 = all_terr_ids[0];
-    let max: u32 = all_terr_ids[all_terr_ids.len() - 1];
-
-    for curr_terr_id in all_terr_ids {
-        let mut other_terr_id: u32 = curr_terr_id;
+fn connect_planar_territories<R: Rng + ?Sized>(
+    territory_map: &mut HashMap<u32, Territory>,
+    rng: &mut R,
+) {
+    let mut all_terr_ids: Vec<u32> = territory_map.keys().copied().collect();
+    all_terr_ids.sort_unstable();
+
+    let seeds: Vec<Point> = planar::place_seeds(all_terr_ids.len(), rng);
+    let cells: Vec<Cell> = planar::voronoi_cells(&seeds);
+
+    for (cur_cell, cur_terr_id) in cells.into_iter().zip(&all_terr_ids) {
+        let this_terr: &mut Territory = territory_map.get_mut(cur_terr_id).unwrap();
+        this_terr.neighbors = cur_cell
+            .neighbors
+            .iter()
+            .map(|cell_index| all_terr_ids[*cell_index])
+            .collect();
+        this_terr.neighbors.sort_unstable();
+        this_terr.centroid = Some(cur_cell.centroid);
+        this_terr.polygon = cur_cell.polygon;
+    }
+}
 
-        while other_terr_id == curr_terr_id {
-            other_terr_id = rand::thread_rng().gen_range(min..=max);
+fn connect_hex_territories<R: Rng + ?Sized>(
+    territory_map: &mut HashMap<u32, Territory>,
+    cells_per_territory: u32,
+    rng: &mut R,
+) {
+    let mut all_terr_ids: Vec<u32> = territory_map.keys().copied().collect();
+    all_terr_ids.sort_unstable();
+
+    let num_territories: u32 = all_terr_ids.len() as u32;
+    let mut grid: HexGrid = HexGrid::for_cells(num_territories * cells_per_territory);
+
+    loop {
+        let regions: Vec<Vec<HexCell>> =
+            match hexmap::grow_regions(&grid, num_territories, cells_per_territory, rng) {
+                Some(regions) => regions,
+                None => {
+                    // Boxed in, try again with a little more room
+                    grid.columns += 1;
+                    grid.rows += 1;
+                    continue;
+                }
+            };
+        let region_neighbors: Vec<Vec<usize>> = hexmap::region_neighbors(&regions);
+
+        let (min_corner, max_corner): (Point, Point) = grid_bounds(&grid);
+        let width: f64 = (max_corner.0 - min_corner.0).max(1.0);
+        let height: f64 = (max_corner.1 - min_corner.1).max(1.0);
+
+        for (cur_index, cur_terr_id) in all_terr_ids.iter().enumerate() {
+            let this_terr: &mut Territory = territory_map.get_mut(cur_terr_id).unwrap();
+            this_terr.neighbors = region_neighbors[cur_index]
+                .iter()
+                .map(|region_index| all_terr_ids[*region_index])
+                .collect();
+
+            let num_cells: f64 = regions[cur_index].len() as f64;
+            let (x_sum, y_sum): (f64, f64) = regions[cur_index]
+                .iter()
+                .map(|cell| hexmap::cell_center(*cell))
+                .fold((0.0, 0.0), |sum, center| {
+                    (sum.0 + center.0, sum.1 + center.1)
+                });
+            this_terr.centroid = Some((
+                (x_sum / num_cells - min_corner.0) / width,
+                (y_sum / num_cells - min_corner.1) / height,
+            ));
+            this_terr.hex_cells = regions[cur_index].clone();
         }
 
-        let this_terr: &mut Territory = territory_map.get_mut(&curr_terr_id).unwrap();
-        if !this_terr.neighbors.contains(&other_terr_id) {
-            this_terr.neighbors.push(other_terr_id);
+        if is_connected(territory_map) {
+            break;
         }
+    }
+}
 
-        let other_terr: &mut Territory = territory_map.get_mut(&other_terr_id).unwrap();
-        if !other_terr.neighbors.contains(&curr_terr_id) {
-            other_terr.neighbors.push(curr_terr_id);
-        }
+// Corners of the box around the grid's cell centers, used to fit hex maps in the unit square
+fn grid_bounds(grid: &HexGrid) -> (Point, Point) {
+    let mut min_corner: Point = (f64::INFINITY, f64::INFINITY);
+    let mut max_corner: Point = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+
+    for cur_cell in grid.cells() {
+        let center: Point = hexmap::cell_center(cur_cell);
+        min_corner = (min_corner.0.min(center.0), min_corner.1.min(center.1));
+        max_corner = (max_corner.0.max(center.0), max_corner.1.max(center.1));
     }
+
+    (min_corner, max_corner)
 }
 
-fn clear_neighbors(territory_map: &mut HashMap<u32, Territory>) {
+pub(crate) fn clear_neighbors(territory_map: &mut HashMap<u32, Territory>) {
     let mut all_terr_ids: Vec<u32> = Vec::new();
 
     for curr_id in territory_map.keys() {
@@ -228,11 +1719,11 @@ fn is_connected(territory_map: &HashMap<u32, Territory>) -> bool {
             queue.push_back(*cur_terr_id);
             visited.insert(*cur_terr_id, true);
 
-            while queue.len() > 0 {
+            while !queue.is_empty() {
                 let w: u32 = queue.pop_front().unwrap();
 
-                if territory_map.contains_key(&(w as u32)) {
-                    let cur_territory = &territory_map[&(w as u32)];
+                if territory_map.contains_key(&w) {
+                    let cur_territory = &territory_map[&w];
 
                     for cur_neighbor in &cur_territory.neighbors {
                         if !visited[cur_neighbor] {
@@ -245,7 +1736,7 @@ fn is_connected(territory_map: &HashMap<u32, Territory>) -> bool {
         }
     }
 
-    return comp_num == 1;
+    comp_num == 1
 }
 
 fn verify_neighbors(territory_map: &HashMap<u32, Territory>) -> bool {
@@ -263,29 +1754,27 @@ fn verify_neighbors(territory_map: &HashMap<u32, Territory>) -> bool {
             }
         }
     }
-    return result;
+    result
 }
 
-fn battle(num_dice_attacker: u32, num_dice_defender: u32) -> bool {
-    let mut attacker_sum: u32 = 0;
-    let mut attacked_sum: u32 = 0;
-
-    let mut die_roll = rand::thread_rng();
-
-    for _cur_dice in 0..(num_dice_attacker - 1) {
-        attacker_sum += die_roll.gen_range(1..7);
-    }
-
-    for _cur_dice in 0..(num_dice_defender - 1) {
-        attacked_sum += die_roll.gen_range(1..7);
-    }
-
-    return attacker_sum > attacked_sum;
+pub fn battle<R: Rng + ?Sized>(
+    num_dice_attacker: u32,
+    num_dice_defender: u32,
+    die_roll: &mut R,
+) -> bool {
+    combat::sum_battle(
+        num_dice_attacker,
+        num_dice_defender,
+        &DiceRules::default(),
+        die_roll,
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::combat::CombatMode;
+    use crate::rules::{CapitalRules, CaptureRule, FortifyRules, ReinforcementMode};
 
     #[test]
     fn new_gameboard() {
@@ -312,6 +1801,320 @@ mod tests {
         }
     }
 
+    #[test]
+    fn new_seeded_gameboard_is_reproducible() {
+        let first: Gameboard = Gameboard::new_seeded(3, 4, 10, 42);
+        let second: Gameboard = Gameboard::new_seeded(3, 4, 10, 42);
+
+        for cur_terr in first.territory_map.values() {
+            let other_terr: &Territory = &second.territory_map[&cur_terr.id];
+            assert_eq!(cur_terr.owner_id, other_terr.owner_id);
+            assert_eq!(cur_terr.num_dice, other_terr.num_dice);
+            assert_eq!(cur_terr.neighbors, other_terr.neighbors);
+        }
+    }
+
+    #[test]
+    fn planar_gameboard_is_connected_with_geometry() {
+        let mut config: BoardConfig = BoardConfig::new(4, 6, 15);
+        config.map_style = MapStyle::Planar;
+        config.seed = Some(11);
+
+        let gameboard: Gameboard = Gameboard::from_config(&config);
+
+        assert_eq!(24, gameboard.territory_map.len());
+        assert!(is_connected(&gameboard.territory_map));
+        assert!(verify_neighbors(&gameboard.territory_map));
+        for cur_terr in gameboard.territory_map.values() {
+            assert!(cur_terr.centroid.is_some());
+            assert!(cur_terr.polygon.len() >= 3);
+            assert!(!cur_terr.neighbors.contains(&cur_terr.id));
+        }
+    }
+
+    #[test]
+    fn hex_gameboard_is_connected_with_cells() {
+        let gameboard: Gameboard = Gameboard::new_hex(3, 5, 12, 6);
+
+        assert_eq!(15, gameboard.territory_map.len());
+        assert!(is_connected(&gameboard.territory_map));
+        assert!(verify_neighbors(&gameboard.territory_map));
+        for cur_terr in gameboard.territory_map.values() {
+            assert_eq!(6, cur_terr.hex_cells.len());
+            let (x, y) = cur_terr.centroid.unwrap();
+            assert!((0.0..=1.0).contains(&x) && (0.0..=1.0).contains(&y));
+        }
+    }
+
+    #[test]
+    fn map_style_from_name() {
+        assert_eq!(Some(MapStyle::Planar), MapStyle::from_name("planar"));
+        assert_eq!(
+            Some(MapStyle::Hex {
+                cells_per_territory: 12
+            }),
+            MapStyle::from_name("hex:12")
+        );
+        assert_eq!(None, MapStyle::from_name("hex:0"));
+        assert_eq!(None, MapStyle::from_name("planar:3"));
+    }
+
+    #[test]
+    fn graph_gameboard_reaches_average_degree() {
+        let gameboard: Gameboard = Gameboard::new_seeded(4, 10, 20, 9);
+        let average_degree: f64 = TopologyOptions::default().average_degree;
+
+        let total_degree: usize = gameboard
+            .territory_map
+            .values()
+            .map(|terr| terr.neighbors.len())
+            .sum();
+        assert_eq!(
+            (average_degree * 40.0 / 2.0).ceil() as usize,
+            total_degree / 2
+        );
+        assert!(is_connected(&gameboard.territory_map));
+        assert!(verify_neighbors(&gameboard.territory_map));
+    }
+
+    #[test]
+    fn saved_board_keeps_generator_metadata() {
+        let mut config: BoardConfig = BoardConfig::new(3, 8, 16);
+        config.seed = Some(4);
+        config.topology.num_continents = 3;
+        config.topology.max_neighbors = Some(5);
+        let gameboard: Gameboard = Gameboard::from_config(&config);
+
+        let loaded: Gameboard = Gameboard::from_json(&gameboard.to_json()).unwrap();
+
+        assert_eq!(Some(4), loaded.metadata.seed);
+        assert_eq!(config.topology, loaded.metadata.topology);
+        assert_eq!(gameboard.territory_map.len(), loaded.territory_map.len());
+        for cur_terr in gameboard.territory_map.values() {
+            assert_eq!(
+                cur_terr.neighbors,
+                loaded.territory_map[&cur_terr.id].neighbors
+            );
+            assert!(cur_terr.neighbors.len() <= 5);
+        }
+    }
+
+    #[test]
+    fn balanced_deal_is_within_tolerance() {
+        let mut config: BoardConfig = BoardConfig::new(3, 8, 20);
+        config.seed = Some(11);
+        config.fairness_tolerance = Some(0.2);
+        let gameboard: Gameboard = Gameboard::from_config(&config);
+
+        let scores: Vec<PositionScore> = fairness::score_positions(&gameboard);
+        assert!(fairness::score_spread(&scores) <= 0.2);
+        assert_eq!(scores, gameboard.metadata.position_scores);
+        for cur_player_id in 0..3 {
+            assert_eq!(8, gameboard.num_territories_owned(cur_player_id));
+        }
+        let total_dice: u32 = gameboard
+            .territory_map
+            .values()
+            .map(|terr| terr.num_dice)
+            .sum();
+        assert_eq!(60, total_dice);
+    }
+
+    #[test]
+    fn handicaps_change_starting_territories_and_dice() {
+        let mut config: BoardConfig = BoardConfig::new(3, 4, 10);
+        config.handicaps.insert(
+            0,
+            Handicap {
+                num_territories: Some(6),
+                num_dice: Some(20),
+            },
+        );
+        config.last_seat_bonus_dice = 3;
+        let gameboard: Gameboard = Gameboard::from_config(&config);
+
+        let dice_owned = |player_id: u32| -> u32 {
+            gameboard
+                .territory_map
+                .values()
+                .filter(|terr| terr.owner_id == player_id)
+                .map(|terr| terr.num_dice)
+                .sum()
+        };
+        assert_eq!(14, gameboard.territory_map.len());
+        assert_eq!(6, gameboard.num_territories_owned(0));
+        assert_eq!(4, gameboard.num_territories_owned(1));
+        assert_eq!(4, gameboard.num_territories_owned(2));
+        assert_eq!(20, dice_owned(0));
+        assert_eq!(10, dice_owned(1));
+        assert_eq!(13, dice_owned(2));
+        assert_eq!(vec![20, 10, 13], gameboard.metadata.starting_dice);
+        assert!(gameboard
+            .territory_map
+            .values()
+            .all(|terr| terr.num_dice <= DEFAULT_MAX_DICE));
+    }
+
+    #[test]
+    fn unsatisfiable_handicaps_are_rejected() {
+        let mut config: BoardConfig = BoardConfig::new(2, 4, 10);
+        config.handicaps.insert(
+            1,
+            Handicap {
+                num_territories: None,
+                num_dice: Some(3),
+            },
+        );
+        assert!(config.validate().is_err());
+
+        config.handicaps.clear();
+        config.last_seat_bonus_dice = 4 * DEFAULT_MAX_DICE;
+        assert!(config.validate().is_err());
+
+        config.last_seat_bonus_dice = 0;
+        config.handicaps.insert(2, Handicap::default());
+        assert!(config.validate().is_err());
+
+        config.handicaps.clear();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn neutral_share_of_the_board() {
+        let mut config: BoardConfig = BoardConfig::new(2, 6, 12);
+        config.neutral_share = 0.25;
+        config.neutral_dice = 3;
+        let gameboard: Gameboard = Gameboard::from_config(&config);
+
+        let neutrals: Vec<&Territory> = gameboard
+            .territory_map
+            .values()
+            .filter(|terr| terr.owner_id == NEUTRAL_OWNER)
+            .collect();
+        assert_eq!(16, gameboard.territory_map.len());
+        assert_eq!(4, neutrals.len());
+        assert!(neutrals.iter().all(|terr| terr.num_dice == 3));
+        assert_eq!(6, gameboard.num_territories_owned(0));
+        assert_eq!(6, gameboard.num_territories_owned(1));
+        assert_eq!(4, gameboard.metadata.num_neutral_territories);
+    }
+
+    #[test]
+    fn neutrals_can_be_captured_but_never_attack() {
+        let mut gameboard: Gameboard = three_in_a_row();
+        let middle: &mut Territory = gameboard.territory_map.get_mut(&2).unwrap();
+        middle.owner_id = NEUTRAL_OWNER;
+        middle.num_dice = 4;
+
+        assert!(!gameboard.can_attack(2, 1));
+        assert!(gameboard.can_attack(1, 2));
+        assert_eq!(vec![(1, 2)], gameboard.legal_attacks(0));
+    }
+
+    #[test]
+    fn classic_attack_removes_dice_one_at_a_time() {
+        let mut gameboard: Gameboard = three_in_a_row();
+        gameboard.rules.combat = CombatMode::Classic { continued: false };
+        gameboard.territory_map.get_mut(&2).unwrap().num_dice = 4;
+        let mut rng = StdRng::seed_from_u64(3);
+
+        gameboard.attack_with_rng(1, 2, &mut rng);
+
+        // Two attack dice against two defense dice, so two dice are lost in total
+        let attacker_dice: u32 = gameboard.territory_map[&1].num_dice;
+        let defender_dice: u32 = gameboard.territory_map[&2].num_dice;
+        assert_eq!(5, attacker_dice + defender_dice);
+        assert_eq!(1, gameboard.territory_map[&2].owner_id);
+    }
+
+    #[test]
+    fn winner_ignores_neutrals() {
+        let mut gameboard: Gameboard = three_in_a_row();
+        gameboard.territory_map.get_mut(&3).unwrap().owner_id = NEUTRAL_OWNER;
+        assert_eq!(None, gameboard.winner());
+
+        gameboard.territory_map.get_mut(&2).unwrap().owner_id = NEUTRAL_OWNER;
+        assert_eq!(Some(0), gameboard.winner());
+    }
+
+    #[test]
+    fn generated_continents_and_names() {
+        let mut config: BoardConfig = BoardConfig::new(2, 9, 18);
+        config.topology.num_continents = 3;
+        config.name_territories = true;
+        let gameboard: Gameboard = Gameboard::from_config(&config);
+
+        assert_eq!(3, gameboard.continents.len());
+        for cur_continent in &gameboard.continents {
+            let num_members: usize = gameboard
+                .territory_map
+                .values()
+                .filter(|terr| terr.continent == Some(cur_continent.id))
+                .count();
+            assert_eq!(6, num_members);
+            assert_eq!(3, cur_continent.bonus);
+            assert!(cur_continent.name.is_some());
+        }
+        assert!(gameboard
+            .territory_map
+            .values()
+            .all(|terr| terr.name.is_some()));
+    }
+
+    #[test]
+    fn owning_a_continent_grants_its_bonus() {
+        let mut gameboard: Gameboard = three_in_a_row();
+        gameboard.continents = vec![
+            Continent {
+                id: 0,
+                name: Some("West".to_owned()),
+                bonus: 2,
+            },
+            Continent {
+                id: 1,
+                name: None,
+                bonus: 5,
+            },
+        ];
+        gameboard.territory_map.get_mut(&1).unwrap().continent = Some(0);
+        gameboard.territory_map.get_mut(&2).unwrap().continent = Some(1);
+        gameboard.territory_map.get_mut(&3).unwrap().continent = Some(1);
+
+        assert_eq!(2, gameboard.continent_bonus(0));
+        assert_eq!(0, gameboard.continent_bonus(1));
+
+        // Largest region of 1 plus the continent bonus of 2
+        gameboard.end_turn();
+        let player_dice: u32 =
+            gameboard.territory_map[&1].num_dice + gameboard.territory_map[&3].num_dice;
+        assert_eq!(7, player_dice);
+    }
+
+    #[test]
+    fn topology_options_only_apply_to_graph_maps() {
+        let mut config: BoardConfig = BoardConfig::new(2, 5, 10);
+        config.map_style = MapStyle::Planar;
+        config.topology.num_continents = 2;
+
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn large_graph_gameboard_is_connected() {
+        let gameboard: Gameboard = Gameboard::new(8, 500, 600);
+
+        assert_eq!(4000, gameboard.territory_map.len());
+        assert!(is_connected(&gameboard.territory_map));
+    }
+
+    #[test]
+    fn single_territory_gameboard() {
+        let gameboard: Gameboard = Gameboard::new(1, 1, 3);
+
+        assert_eq!(0, gameboard.territory_map[&0].neighbors.len());
+        assert_eq!(3, gameboard.territory_map[&0].num_dice);
+    }
+
     #[test]
     fn is_connected_one_node() {
         let terr_one = Territory {
@@ -319,12 +2122,13 @@ mod tests {
             num_dice: 1,
             owner_id: 0,
             neighbors: Vec::new(),
+            ..Default::default()
         };
 
         let mut territory_map: HashMap<u32, Territory> = HashMap::new();
         territory_map.insert(1, terr_one);
 
-        assert_eq!(true, is_connected(&territory_map));
+        assert!(is_connected(&territory_map));
     }
 
     #[test]
@@ -334,6 +2138,7 @@ mod tests {
             num_dice: 1,
             owner_id: 0,
             neighbors: vec![2],
+            ..Default::default()
         };
 
         let terr_two = Territory {
@@ -341,13 +2146,14 @@ mod tests {
             num_dice: 1,
             owner_id: 0,
             neighbors: vec![1],
+            ..Default::default()
         };
 
         let mut territory_map: HashMap<u32, Territory> = HashMap::new();
         territory_map.insert(1, terr_one);
         territory_map.insert(2, terr_two);
 
-        assert_eq!(true, is_connected(&territory_map));
+        assert!(is_connected(&territory_map));
     }
 
     #[test]
@@ -357,6 +2163,7 @@ mod tests {
             num_dice: 1,
             owner_id: 0,
             neighbors: Vec::new(),
+            ..Default::default()
         };
 
         let terr_two = Territory {
@@ -364,13 +2171,14 @@ mod tests {
             num_dice: 1,
             owner_id: 0,
             neighbors: Vec::new(),
+            ..Default::default()
         };
 
         let mut territory_map: HashMap<u32, Territory> = HashMap::new();
         territory_map.insert(1, terr_one);
         territory_map.insert(2, terr_two);
 
-        assert_eq!(false, is_connected(&territory_map));
+        assert!(!is_connected(&territory_map));
     }
 
     #[test]
@@ -380,6 +2188,7 @@ mod tests {
             num_dice: 1,
             owner_id: 0,
             neighbors: Vec::new(),
+            ..Default::default()
         };
 
         let terr_two = Territory {
@@ -387,6 +2196,7 @@ mod tests {
             num_dice: 1,
             owner_id: 0,
             neighbors: Vec::new(),
+            ..Default::default()
         };
 
         let terr_three = Territory {
@@ -394,6 +2204,7 @@ mod tests {
             num_dice: 1,
             owner_id: 0,
             neighbors: Vec::new(),
+            ..Default::default()
         };
 
         let mut territory_map: HashMap<u32, Territory> = HashMap::new();
@@ -401,7 +2212,7 @@ mod tests {
         territory_map.insert(2, terr_two);
         territory_map.insert(3, terr_three);
 
-        assert_eq!(false, is_connected(&territory_map));
+        assert!(!is_connected(&territory_map));
     }
 
     #[test]
@@ -411,6 +2222,7 @@ mod tests {
             num_dice: 1,
             owner_id: 0,
             neighbors: vec![2],
+            ..Default::default()
         };
 
         let terr_two = Territory {
@@ -418,6 +2230,7 @@ mod tests {
             num_dice: 1,
             owner_id: 0,
             neighbors: vec![1],
+            ..Default::default()
         };
 
         let terr_three = Territory {
@@ -425,6 +2238,7 @@ mod tests {
             num_dice: 1,
             owner_id: 0,
             neighbors: Vec::new(),
+            ..Default::default()
         };
 
         let mut territory_map: HashMap<u32, Territory> = HashMap::new();
@@ -432,7 +2246,7 @@ mod tests {
         territory_map.insert(2, terr_two);
         territory_map.insert(3, terr_three);
 
-        assert_eq!(false, is_connected(&territory_map));
+        assert!(!is_connected(&territory_map));
     }
 
     #[test]
@@ -442,6 +2256,7 @@ mod tests {
             num_dice: 1,
             owner_id: 0,
             neighbors: vec![2, 3],
+            ..Default::default()
         };
 
         let terr_two = Territory {
@@ -449,6 +2264,7 @@ mod tests {
             num_dice: 1,
             owner_id: 0,
             neighbors: vec![1, 3],
+            ..Default::default()
         };
 
         let terr_three = Territory {
@@ -456,6 +2272,7 @@ mod tests {
             num_dice: 1,
             owner_id: 0,
             neighbors: vec![1, 2],
+            ..Default::default()
         };
 
         let mut territory_map: HashMap<u32, Territory> = HashMap::new();
@@ -463,7 +2280,7 @@ mod tests {
         territory_map.insert(2, terr_two);
         territory_map.insert(3, terr_three);
 
-        assert_eq!(true, is_connected(&territory_map));
+        assert!(is_connected(&territory_map));
     }
 
     #[test]
@@ -473,6 +2290,7 @@ mod tests {
             num_dice: 1,
             owner_id: 0,
             neighbors: vec![2, 3],
+            ..Default::default()
         };
 
         let terr_two = Territory {
@@ -480,6 +2298,7 @@ mod tests {
             num_dice: 1,
             owner_id: 0,
             neighbors: vec![1, 3],
+            ..Default::default()
         };
 
         let mut territory_map: HashMap<u32, Territory> = HashMap::new();
@@ -492,6 +2311,656 @@ mod tests {
             assert_eq!(0, cur_terr.neighbors.len());
         }
     }
+
+    fn three_in_a_row() -> Gameboard {
+        let terr_one = Territory {
+            id: 1,
+            num_dice: 3,
+            owner_id: 0,
+            neighbors: vec![2],
+            ..Default::default()
+        };
+
+        let terr_two = Territory {
+            id: 2,
+            num_dice: 2,
+            owner_id: 1,
+            neighbors: vec![1, 3],
+            ..Default::default()
+        };
+
+        let terr_three = Territory {
+            id: 3,
+            num_dice: 1,
+            owner_id: 0,
+            neighbors: vec![2],
+            ..Default::default()
+        };
+
+        let mut territory_map: HashMap<u32, Territory> = HashMap::new();
+        territory_map.insert(1, terr_one);
+        territory_map.insert(2, terr_two);
+        territory_map.insert(3, terr_three);
+
+        Gameboard::from_territory_map(territory_map, 2)
+    }
+
+    #[test]
+    fn legal_attacks_need_more_than_one_die() {
+        let gameboard: Gameboard = three_in_a_row();
+
+        assert_eq!(vec![(1, 2)], gameboard.legal_attacks(0));
+        assert_eq!(vec![(2, 1), (2, 3)], gameboard.legal_attacks(1));
+    }
+
+    #[test]
+    fn largest_region_counts_connected_territories() {
+        let mut gameboard: Gameboard = three_in_a_row();
+
+        assert_eq!(1, gameboard.largest_region(0));
+        assert_eq!(1, gameboard.largest_region(1));
+
+        gameboard.territory_map.get_mut(&2).unwrap().owner_id = 0;
+        assert_eq!(3, gameboard.largest_region(0));
+        assert_eq!(0, gameboard.largest_region(1));
+    }
+
+    #[test]
+    fn end_turn_reinforces_and_advances() {
+        let mut gameboard: Gameboard = three_in_a_row();
+
+        gameboard.end_turn();
+
+        let player_zero_dice: u32 =
+            gameboard.territory_map[&1].num_dice + gameboard.territory_map[&3].num_dice;
+        assert_eq!(5, player_zero_dice);
+        assert_eq!(1, gameboard.current_player_id);
+        assert_eq!(2, gameboard.turn_number);
+
+        gameboard.end_turn();
+        assert_eq!(0, gameboard.current_player_id);
+    }
+
+    #[test]
+    fn end_turn_respects_max_dice() {
+        let mut gameboard: Gameboard = three_in_a_row();
+        gameboard.max_dice = 3;
+
+        gameboard.end_turn();
+
+        assert_eq!(3, gameboard.territory_map[&1].num_dice);
+        assert_eq!(2, gameboard.territory_map[&3].num_dice);
+    }
+
+    // Player 0 holds 1, 2 and 3 in a row, player 1 holds 4 next to 1
+    fn fortify_board(path: FortifyPath) -> Gameboard {
+        let neighbors: [Vec<u32>; 4] = [vec![2, 4], vec![1, 3], vec![2], vec![1]];
+        let territory_map: HashMap<u32, Territory> = (1..=4)
+            .map(|cur_id| {
+                (
+                    cur_id,
+                    Territory {
+                        id: cur_id,
+                        num_dice: 4,
+                        owner_id: if cur_id == 4 { 1 } else { 0 },
+                        neighbors: neighbors[cur_id as usize - 1].clone(),
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect();
+
+        let mut gameboard: Gameboard = Gameboard::from_territory_map(territory_map, 2);
+        gameboard.rules.fortify = Some(FortifyRules { path, max_moves: 2 });
+        gameboard
+    }
+
+    #[test]
+    fn moves_follow_the_fortify_path() {
+        let gameboard: Gameboard = fortify_board(FortifyPath::Connected);
+        assert!(gameboard.can_move(1, 3, 3));
+        assert!(!gameboard.can_move(1, 3, 4));
+        assert!(!gameboard.can_move(1, 4, 1));
+
+        let gameboard: Gameboard = fortify_board(FortifyPath::Neighbors);
+        assert!(!gameboard.can_move(1, 3, 1));
+        assert!(gameboard.can_move(1, 2, 1));
+
+        let mut gameboard: Gameboard = three_in_a_row();
+        assert!(!gameboard.can_move(1, 3, 1));
+        gameboard.rules.fortify = Some(FortifyRules::default());
+        // Territory 2 of player 1 cuts 1 off from 3
+        assert!(!gameboard.can_move(1, 3, 1));
+    }
+
+    #[test]
+    fn moving_ends_attacks_until_the_next_turn() {
+        let mut gameboard: Gameboard = fortify_board(FortifyPath::Connected);
+        assert!(gameboard.can_attack(1, 4));
+
+        gameboard.move_dice(1, 2, 2);
+        assert_eq!(2, gameboard.territory_map[&1].num_dice);
+        assert_eq!(6, gameboard.territory_map[&2].num_dice);
+        assert!(!gameboard.can_attack(1, 4));
+        assert!(gameboard.legal_attacks(0).is_empty());
+
+        gameboard.move_dice(3, 2, 1);
+        assert!(!gameboard.can_move(3, 2, 1));
+
+        gameboard.end_turn();
+        assert_eq!(Phase::Attack, gameboard.phase);
+        assert_eq!(0, gameboard.moves_this_turn);
+        assert!(gameboard.can_attack(4, 1));
+    }
+
+    #[test]
+    fn capture_rule_decides_how_many_dice_advance() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let capture = |rule: CaptureRule, advance: Option<u32>, rng: &mut StdRng| -> (u32, u32) {
+            let mut gameboard: Gameboard = three_in_a_row();
+            gameboard.rules.capture = rule;
+            gameboard.territory_map.get_mut(&1).unwrap().num_dice = 5;
+            // A single defending die rolls nothing, so the attack always succeeds
+            gameboard.territory_map.get_mut(&2).unwrap().num_dice = 1;
+
+            gameboard.attack_and_advance_with_rng(1, 2, advance, rng);
+            assert_eq!(0, gameboard.territory_map[&2].owner_id);
+            (
+                gameboard.territory_map[&1].num_dice,
+                gameboard.territory_map[&2].num_dice,
+            )
+        };
+
+        assert_eq!((1, 5), capture(CaptureRule::MoveAll, Some(2), &mut rng));
+        assert_eq!((4, 2), capture(CaptureRule::MoveMinimum, None, &mut rng));
+        assert_eq!((3, 3), capture(CaptureRule::Choose, Some(2), &mut rng));
+        assert_eq!((1, 5), capture(CaptureRule::Choose, None, &mut rng));
+    }
+
+    #[test]
+    fn draft_turns_start_with_placing_reinforcements() {
+        let mut gameboard: Gameboard = three_in_a_row();
+        gameboard.rules.reinforcements = ReinforcementMode::Draft;
+
+        gameboard.end_turn();
+        // Player 0 got nothing for ending their turn, player 1 drafts a region of 1
+        assert_eq!(
+            4,
+            gameboard.territory_map[&1].num_dice + gameboard.territory_map[&3].num_dice
+        );
+        assert_eq!(Phase::Reinforce, gameboard.phase);
+        assert_eq!(1, gameboard.pending_reinforcements);
+        assert!(!gameboard.can_attack(2, 1));
+        assert!(gameboard.legal_attacks(1).is_empty());
+        assert!(!gameboard.can_place(1, 1));
+        assert!(!gameboard.can_place(2, 2));
+
+        gameboard.place_dice(2, 1);
+        assert_eq!(3, gameboard.territory_map[&2].num_dice);
+        assert_eq!(Phase::Attack, gameboard.phase);
+        assert!(!gameboard.can_place(2, 1));
+        assert!(gameboard.can_attack(2, 1));
+    }
+
+    #[test]
+    fn draft_is_capped_by_free_space() {
+        let mut gameboard: Gameboard = three_in_a_row();
+        gameboard.rules.reinforcements = ReinforcementMode::Draft;
+        gameboard.max_dice = 2;
+
+        gameboard.end_turn();
+
+        assert_eq!(Phase::Attack, gameboard.phase);
+        assert_eq!(0, gameboard.pending_reinforcements);
+    }
+
+    // Territory 1 of player 0 next to player 1's capital 2, player 1 also holds 3
+    fn capital_board(on_loss: CapitalLoss, capital_victory: bool) -> Gameboard {
+        let mut gameboard: Gameboard = three_in_a_row();
+        gameboard.territory_map.get_mut(&1).unwrap().num_dice = 8;
+        gameboard.territory_map.get_mut(&1).unwrap().capital = true;
+        gameboard.territory_map.get_mut(&1).unwrap().capital_of = Some(0);
+        gameboard.territory_map.get_mut(&2).unwrap().num_dice = 1;
+        gameboard.territory_map.get_mut(&2).unwrap().capital = true;
+        gameboard.territory_map.get_mut(&2).unwrap().capital_of = Some(1);
+        gameboard.territory_map.get_mut(&3).unwrap().owner_id = 1;
+        gameboard.rules.capitals = Some(CapitalRules {
+            defense_bonus: 1,
+            on_loss,
+            capital_victory,
+        });
+        gameboard
+    }
+
+    // Attacks from 1 to 2 until it falls
+    fn take_capital(gameboard: &mut Gameboard) {
+        let mut rng = StdRng::seed_from_u64(9);
+        while gameboard.territory_map[&2].owner_id == 1 {
+            gameboard.territory_map.get_mut(&1).unwrap().num_dice = 8;
+            gameboard.attack_with_rng(1, 2, &mut rng);
+            assert!(gameboard.territory_map[&2].num_dice >= 1);
+        }
+    }
+
+    #[test]
+    fn capitals_defend_with_a_bonus() {
+        let gameboard: Gameboard = capital_board(CapitalLoss::Eliminate, false);
+
+        assert_eq!(1, gameboard.defense_bonus(2));
+        assert_eq!(0, gameboard.defense_bonus(3));
+        assert!(gameboard.holds_capital(1));
+    }
+
+    #[test]
+    fn losing_the_last_capital_eliminates() {
+        let mut gameboard: Gameboard = capital_board(CapitalLoss::Eliminate, false);
+
+        take_capital(&mut gameboard);
+
+        assert!(gameboard.is_eliminated(1));
+        assert_eq!(1, gameboard.territory_map[&3].owner_id);
+        assert_eq!(None, gameboard.winner());
+        gameboard.end_turn();
+        assert_eq!(0, gameboard.current_player_id);
+    }
+
+    #[test]
+    fn losing_the_last_capital_transfers_territories() {
+        let mut gameboard: Gameboard = capital_board(CapitalLoss::Transfer, false);
+
+        take_capital(&mut gameboard);
+
+        assert_eq!(0, gameboard.territory_map[&3].owner_id);
+        assert_eq!(Some(0), gameboard.winner());
+    }
+
+    #[test]
+    fn holding_every_capital_wins() {
+        let mut gameboard: Gameboard = capital_board(CapitalLoss::Eliminate, true);
+        assert_eq!(None, gameboard.winner());
+
+        take_capital(&mut gameboard);
+
+        assert_eq!(Some(0), gameboard.winner());
+    }
+
+    #[test]
+    fn moving_a_capital_keeps_captured_ones() {
+        let mut gameboard: Gameboard = capital_board(CapitalLoss::Eliminate, false);
+        gameboard.num_players = 3;
+        take_capital(&mut gameboard);
+        gameboard.territory_map.get_mut(&3).unwrap().owner_id = 0;
+
+        assert!(!gameboard.can_designate_capital(2));
+        assert!(gameboard.can_designate_capital(3));
+        gameboard.designate_capital(3);
+
+        assert!(!gameboard.territory_map[&1].capital);
+        assert_eq!(Some(1), gameboard.territory_map[&2].capital_of);
+        assert_eq!(Some(0), gameboard.territory_map[&3].capital_of);
+    }
+
+    #[test]
+    fn capitals_are_assigned_and_can_be_moved_early() {
+        let mut gameboard: Gameboard = Gameboard::new_seeded(3, 4, 8, 2);
+        gameboard.rules.capitals = Some(CapitalRules::default());
+        gameboard.assign_capitals_with_rng(&mut StdRng::seed_from_u64(2));
+
+        for cur_player_id in 0..3 {
+            let num_capitals: usize = gameboard
+                .territory_map
+                .values()
+                .filter(|terr| terr.capital && terr.owner_id == cur_player_id)
+                .count();
+            assert_eq!(1, num_capitals);
+        }
+
+        let other: u32 = gameboard
+            .territory_map
+            .values()
+            .find(|terr| terr.owner_id == 0 && !terr.capital)
+            .unwrap()
+            .id;
+        assert!(gameboard.can_designate_capital(other));
+        gameboard.designate_capital(other);
+        assert!(gameboard.territory_map[&other].capital);
+        assert!(gameboard.holds_capital(0));
+
+        gameboard.turn_number = 4;
+        assert!(!gameboard.can_designate_capital(other));
+    }
+
+    // Player 0 holds 0..6, player 1 holds 6..10, all in a row
+    fn victory_board() -> Gameboard {
+        let territory_map: HashMap<u32, Territory> = (0..10)
+            .map(|cur_id: u32| {
+                let neighbors: Vec<u32> = [cur_id.checked_sub(1), Some(cur_id + 1)]
+                    .iter()
+                    .flatten()
+                    .copied()
+                    .filter(|neighbor| *neighbor < 10)
+                    .collect();
+                let territory = Territory {
+                    id: cur_id,
+                    num_dice: 2,
+                    owner_id: if cur_id < 6 { 0 } else { 1 },
+                    neighbors,
+                    ..Default::default()
+                };
+                (cur_id, territory)
+            })
+            .collect();
+
+        Gameboard::from_territory_map(territory_map, 2)
+    }
+
+    #[test]
+    fn territory_share_wins() {
+        let mut gameboard: Gameboard = victory_board();
+        gameboard.rules.victory.territory_percent = Some(70);
+        assert_eq!(None, gameboard.winner());
+
+        gameboard.territory_map.get_mut(&6).unwrap().owner_id = 0;
+        assert_eq!(Some(0), gameboard.winner());
+    }
+
+    #[test]
+    fn score_limit_counts_continents() {
+        let mut gameboard: Gameboard = victory_board();
+        gameboard.rules.victory.score_limit = Some(8);
+        assert_eq!(None, gameboard.winner());
+
+        gameboard.continents = vec![Continent {
+            id: 0,
+            name: None,
+            bonus: 3,
+        }];
+        for cur_id in 6..10 {
+            gameboard.territory_map.get_mut(&cur_id).unwrap().continent = Some(0);
+        }
+        assert_eq!(7, gameboard.score(1));
+        assert_eq!(None, gameboard.winner());
+
+        gameboard.territory_map.get_mut(&5).unwrap().owner_id = 1;
+        assert_eq!(Some(1), gameboard.winner());
+    }
+
+    #[test]
+    fn turn_limit_goes_to_the_leader() {
+        let mut gameboard: Gameboard = victory_board();
+        gameboard.rules.victory.turn_limit = Some(2);
+        gameboard.territory_map.get_mut(&0).unwrap().owner_id = 1;
+        gameboard.territory_map.get_mut(&9).unwrap().num_dice = 8;
+
+        // Two rounds of two players
+        for _ in 0..3 {
+            gameboard.end_turn();
+            assert_eq!(None, gameboard.winner());
+        }
+
+        gameboard.end_turn();
+        // Five territories each, player 1 has more dice
+        assert_eq!(5, gameboard.turn_number);
+        assert_eq!(Some(1), gameboard.winner());
+    }
+
+    #[test]
+    fn teammates_cannot_attack_each_other() {
+        let mut gameboard: Gameboard = three_in_a_row();
+        gameboard.territory_map.get_mut(&3).unwrap().owner_id = 2;
+        gameboard.num_players = 3;
+        gameboard.territory_map.get_mut(&2).unwrap().num_dice = 3;
+        assert!(gameboard.can_join_team(2, 1));
+        gameboard.join_team(2, 1);
+
+        assert_eq!(vec![0, 1, 1], gameboard.teams);
+        assert!(gameboard.can_attack(2, 1));
+        assert!(!gameboard.can_attack(2, 3));
+        assert_eq!(vec![(2, 1)], gameboard.legal_attacks(1));
+        assert_eq!(None, gameboard.winner());
+
+        gameboard.territory_map.get_mut(&1).unwrap().owner_id = 2;
+        assert_eq!(Some(1), gameboard.winner());
+
+        gameboard.end_turn_with_rng(&mut StdRng::seed_from_u64(1));
+        assert!(!gameboard.can_join_team(0, 1));
+    }
+
+    #[test]
+    fn pacts_stop_attacks_until_they_expire() {
+        let mut gameboard: Gameboard = three_in_a_row();
+        assert!(!gameboard.can_propose_pact(0, 1, 2));
+        gameboard.rules.diplomacy = Some(DiplomacyRules::default());
+
+        assert!(!gameboard.can_propose_pact(0, 0, 2));
+        assert!(!gameboard.can_propose_pact(0, 1, 6));
+        assert!(!gameboard.can_accept_pact(1, 0));
+        assert!(gameboard.can_propose_pact(0, 1, 2));
+        gameboard.propose_pact(0, 1, 2);
+        assert!(gameboard.can_attack(1, 2));
+
+        assert!(!gameboard.can_accept_pact(0, 1));
+        assert!(gameboard.can_accept_pact(1, 0));
+        gameboard.accept_pact(1, 0);
+        assert!(gameboard.proposals.is_empty());
+        assert!(!gameboard.can_attack(1, 2));
+        assert!(gameboard.legal_attacks(0).is_empty());
+        assert!(!gameboard.can_propose_pact(1, 0, 1));
+
+        // Two rounds of two players
+        for _ in 0..3 {
+            gameboard.end_turn_with_rng(&mut StdRng::seed_from_u64(1));
+            assert!(gameboard.has_pact(0, 1));
+        }
+        gameboard.end_turn_with_rng(&mut StdRng::seed_from_u64(1));
+        assert!(!gameboard.has_pact(0, 1));
+
+        let kinds: Vec<EventKind> = gameboard.events.iter().map(|event| event.kind).collect();
+        assert_eq!(
+            vec![
+                EventKind::PactProposed {
+                    proposer: 0,
+                    partner: 1,
+                    turns: 2
+                },
+                EventKind::PactAccepted {
+                    proposer: 0,
+                    partner: 1,
+                    expires_on_turn: 5
+                },
+                EventKind::PactExpired {
+                    first_player_id: 0,
+                    second_player_id: 1
+                },
+            ],
+            kinds
+        );
+        assert_eq!(5, gameboard.events[2].turn_number);
+    }
+
+    #[test]
+    fn stale_proposals_cannot_be_accepted() {
+        let mut gameboard: Gameboard = three_in_a_row();
+        gameboard.rules.diplomacy = Some(DiplomacyRules::default());
+        gameboard.propose_pact(0, 1, 2);
+
+        gameboard.rules.diplomacy = None;
+        assert!(!gameboard.can_accept_pact(1, 0));
+        gameboard.rules.diplomacy = Some(DiplomacyRules::default());
+
+        gameboard.join_team(1, 0);
+        assert!(!gameboard.can_accept_pact(1, 0));
+        gameboard.teams.clear();
+        assert!(gameboard.can_accept_pact(1, 0));
+
+        for cur_terr in gameboard.territory_map.values_mut() {
+            cur_terr.owner_id = 1;
+        }
+        assert!(!gameboard.can_accept_pact(1, 0));
+    }
+
+    #[test]
+    fn long_pacts_last_until_the_end_of_the_game() {
+        let mut gameboard: Gameboard = three_in_a_row();
+        gameboard.rules.diplomacy = Some(DiplomacyRules {
+            max_turns: u32::MAX,
+            allow_breaking: true,
+        });
+        gameboard.propose_pact(0, 1, u32::MAX);
+        gameboard.accept_pact(1, 0);
+
+        assert_eq!(u32::MAX, gameboard.pacts[0].expires_on_turn);
+    }
+
+    #[test]
+    fn breaking_pacts_can_be_forbidden() {
+        let mut gameboard: Gameboard = three_in_a_row();
+        gameboard.rules.diplomacy = Some(DiplomacyRules {
+            max_turns: 3,
+            allow_breaking: false,
+        });
+        gameboard.propose_pact(1, 0, 3);
+        gameboard.accept_pact(0, 1);
+
+        assert!(!gameboard.can_break_pact(0, 1));
+        gameboard.rules.diplomacy = Some(DiplomacyRules::default());
+        assert!(gameboard.can_break_pact(0, 1));
+        gameboard.break_pact(0, 1);
+
+        assert!(gameboard.can_attack(1, 2));
+        assert_eq!(
+            EventKind::PactBroken {
+                breaker: 0,
+                partner: 1
+            },
+            gameboard.events.last().unwrap().kind
+        );
+        let saved: Gameboard = Gameboard::from_json(&gameboard.to_json()).unwrap();
+        assert_eq!(gameboard.events, saved.events);
+    }
+
+    #[test]
+    fn fog_hides_territories_away_from_the_border() {
+        let mut gameboard: Gameboard = three_in_a_row();
+        gameboard.territory_map.get_mut(&3).unwrap().owner_id = 1;
+        gameboard.territory_map.get_mut(&3).unwrap().neighbors = vec![2, 4];
+        gameboard.territory_map.insert(
+            4,
+            Territory {
+                id: 4,
+                num_dice: 5,
+                owner_id: 1,
+                neighbors: vec![3],
+                capital: true,
+                ..Default::default()
+            },
+        );
+
+        let view: Gameboard = gameboard.view_for(0);
+        assert!(!view.territory_map[&4].hidden);
+
+        gameboard.rules.fog = FogOfWar::Dice;
+        let view: Gameboard = gameboard.view_for(0);
+        assert!(!view.territory_map[&2].hidden);
+        assert!(view.territory_map[&3].hidden);
+        let hidden: &Territory = &view.territory_map[&4];
+        assert!(hidden.hidden);
+        assert_eq!(
+            (0, 1, true),
+            (hidden.num_dice, hidden.owner_id, hidden.capital)
+        );
+        assert!(!gameboard.can_see(0, 4));
+        assert!(gameboard.can_see(1, 1));
+
+        gameboard.rules.fog = FogOfWar::Ownership;
+        let hidden: Territory = gameboard.view_for(0).territory_map[&4].clone();
+        assert_eq!(
+            (0, NEUTRAL_OWNER, false),
+            (hidden.num_dice, hidden.owner_id, hidden.capital)
+        );
+        assert_eq!(5, gameboard.territory_map[&4].num_dice);
+
+        // Teammates share what they see
+        gameboard.territory_map.get_mut(&4).unwrap().owner_id = 2;
+        gameboard.num_players = 3;
+        gameboard.join_team(2, 0);
+        assert!(!gameboard.view_for(0).territory_map[&3].hidden);
+    }
+
+    #[test]
+    fn teams_are_fixed_by_the_first_attack() {
+        let mut gameboard: Gameboard = three_in_a_row();
+        gameboard.territory_map.get_mut(&3).unwrap().owner_id = 2;
+        gameboard.num_players = 3;
+        assert!(gameboard.can_join_team(2, 1));
+
+        gameboard.attack_with_rng(1, 2, &mut StdRng::seed_from_u64(1));
+
+        assert_eq!(1, gameboard.turn_number);
+        assert!(!gameboard.can_join_team(2, 1));
+    }
+
+    #[test]
+    fn teams_need_an_opponent() {
+        let gameboard: Gameboard = three_in_a_row();
+
+        assert_eq!(2, gameboard.num_teams_in_play());
+        assert!(!gameboard.can_join_team(1, 0));
+        assert!(!gameboard.can_join_team(0, 1));
+    }
+
+    #[test]
+    fn victory_conditions_count_teams_together() {
+        let mut gameboard: Gameboard = victory_board();
+        gameboard.num_players = 3;
+        for cur_id in 0..10 {
+            gameboard.territory_map.get_mut(&cur_id).unwrap().owner_id = match cur_id {
+                0..=3 => 0,
+                4..=6 => 1,
+                _ => 2,
+            };
+        }
+        gameboard.rules.victory.territory_percent = Some(60);
+        assert_eq!(None, gameboard.winner());
+        gameboard.rules.victory.territory_percent = None;
+        gameboard.rules.victory.turn_limit = Some(1);
+        gameboard.turn_number = 4;
+        assert_eq!(Some(0), gameboard.winner());
+
+        // Players 1 and 2 hold six territories together
+        gameboard.join_team(2, 1);
+        assert_eq!(Some(1), gameboard.winner());
+        gameboard.rules.victory.turn_limit = None;
+        gameboard.turn_number = 1;
+        gameboard.rules.victory.territory_percent = Some(60);
+        assert_eq!(Some(1), gameboard.winner());
+        gameboard.rules.victory.territory_percent = None;
+        gameboard.rules.victory.score_limit = Some(6);
+        assert_eq!(6, gameboard.score(2));
+        assert_eq!(Some(1), gameboard.winner());
+    }
+
+    #[test]
+    fn team_regions_count_teammates() {
+        let mut gameboard: Gameboard = three_in_a_row();
+        gameboard.territory_map.get_mut(&3).unwrap().owner_id = 2;
+        gameboard.num_players = 3;
+        gameboard.join_team(2, 1);
+
+        assert_eq!(1, gameboard.num_reinforcements(1));
+        gameboard.rules.team_regions = true;
+        assert_eq!(2, gameboard.num_reinforcements(1));
+        assert_eq!(1, gameboard.num_reinforcements(0));
+    }
+
+    #[test]
+    fn winner_when_one_owner_remains() {
+        let mut gameboard: Gameboard = three_in_a_row();
+        assert_eq!(None, gameboard.winner());
+
+        gameboard.territory_map.get_mut(&2).unwrap().owner_id = 0;
+        assert_eq!(Some(0), gameboard.winner());
+    }
 }
 
 #[test]
@@ -501,12 +2970,13 @@ fn verify_neighbors_one_terr() {
         num_dice: 1,
         owner_id: 0,
         neighbors: Vec::new(),
+        ..Default::default()
     };
 
     let mut territory_map: HashMap<u32, Territory> = HashMap::new();
     territory_map.insert(1, terr_one);
 
-    assert_eq!(true, verify_neighbors(&territory_map));
+    assert!(verify_neighbors(&territory_map));
 }
 
 #[test]
@@ -516,6 +2986,7 @@ fn verify_neighbors_two_terrs_good() {
         num_dice: 1,
         owner_id: 0,
         neighbors: vec![2],
+        ..Default::default()
     };
 
     let terr_two = Territory {
@@ -523,13 +2994,14 @@ fn verify_neighbors_two_terrs_good() {
         num_dice: 1,
         owner_id: 0,
         neighbors: vec![1],
+        ..Default::default()
     };
 
     let mut territory_map: HashMap<u32, Territory> = HashMap::new();
     territory_map.insert(1, terr_one);
     territory_map.insert(2, terr_two);
 
-    assert_eq!(true, verify_neighbors(&territory_map));
+    assert!(verify_neighbors(&territory_map));
 }
 
 #[test]
@@ -539,6 +3011,7 @@ fn verify_neighbors_two_terrs_bad() {
         num_dice: 1,
         owner_id: 0,
         neighbors: vec![2],
+        ..Default::default()
     };
 
     let terr_two = Territory {
@@ -546,13 +3019,14 @@ fn verify_neighbors_two_terrs_bad() {
         num_dice: 1,
         owner_id: 0,
         neighbors: Vec::new(),
+        ..Default::default()
     };
 
     let mut territory_map: HashMap<u32, Territory> = HashMap::new();
     territory_map.insert(1, terr_one);
     territory_map.insert(2, terr_two);
 
-    assert_eq!(false, verify_neighbors(&territory_map));
+    assert!(!verify_neighbors(&territory_map));
 }
 
 #[test]
@@ -562,6 +3036,7 @@ fn verify_neighbors_three_terrs_good() {
         num_dice: 1,
         owner_id: 0,
         neighbors: vec![2],
+        ..Default::default()
     };
 
     let terr_two = Territory {
@@ -569,6 +3044,7 @@ fn verify_neighbors_three_terrs_good() {
         num_dice: 1,
         owner_id: 0,
         neighbors: vec![1, 3],
+        ..Default::default()
     };
 
     let terr_three = Territory {
@@ -576,6 +3052,7 @@ fn verify_neighbors_three_terrs_good() {
         num_dice: 1,
         owner_id: 0,
         neighbors: vec![2],
+        ..Default::default()
     };
 
     let mut territory_map: HashMap<u32, Territory> = HashMap::new();
@@ -583,7 +3060,7 @@ fn verify_neighbors_three_terrs_good() {
     territory_map.insert(2, terr_two);
     territory_map.insert(3, terr_three);
 
-    assert_eq!(true, verify_neighbors(&territory_map));
+    assert!(verify_neighbors(&territory_map));
 }
 
 #[test]
@@ -593,6 +3070,7 @@ fn verify_neighbors_three_terrs_bad() {
         num_dice: 1,
         owner_id: 0,
         neighbors: vec![2],
+        ..Default::default()
     };
 
     let terr_two = Territory {
@@ -600,6 +3078,7 @@ fn verify_neighbors_three_terrs_bad() {
         num_dice: 1,
         owner_id: 0,
         neighbors: vec![1, 3],
+        ..Default::default()
     };
 
     let terr_three = Territory {
@@ -607,6 +3086,7 @@ fn verify_neighbors_three_terrs_bad() {
         num_dice: 1,
         owner_id: 0,
         neighbors: vec![1, 2],
+        ..Default::default()
     };
 
     let mut territory_map: HashMap<u32, Territory> = HashMap::new();
@@ -614,5 +3094,5 @@ fn verify_neighbors_three_terrs_bad() {
     territory_map.insert(2, terr_two);
     territory_map.insert(3, terr_three);
 
-    assert_eq!(false, verify_neighbors(&territory_map));
+    assert!(!verify_neighbors(&territory_map));
 }
@@ -0,0 +1,201 @@
+use crate::planar::Point;
+use rand::prelude::SliceRandom;
+use rand::Rng;
+use std::collections::HashMap;
+
+/// A hex cell in axial coordinates `(q, r)` on a pointy-top grid.
+pub type HexCell = (i32, i32);
+
+const DIRECTIONS: [HexCell; 6] = [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
+
+// Room to spare so regions rarely box each other in
+const GRID_SLACK: f64 = 1.6;
+
+pub fn cell_neighbors(cell: HexCell) -> Vec<HexCell> {
+    DIRECTIONS
+        .iter()
+        .map(|(dq, dr)| (cell.0 + dq, cell.1 + dr))
+        .collect()
+}
+
+// Center of a unit-size hex in pixel space
+pub fn cell_center(cell: HexCell) -> Point {
+    let q: f64 = f64::from(cell.0);
+    let r: f64 = f64::from(cell.1);
+
+    (3.0_f64.sqrt() * (q + r / 2.0), 1.5 * r)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HexGrid {
+    pub columns: i32,
+    pub rows: i32,
+}
+
+impl HexGrid {
+    pub fn for_cells(num_cells: u32) -> HexGrid {
+        let side: i32 = (f64::from(num_cells) * GRID_SLACK).sqrt().ceil() as i32;
+
+        HexGrid {
+            columns: side.max(1),
+            rows: side.max(1),
+        }
+    }
+
+    // Rows are offset so the grid is a rectangle rather than a rhombus
+    pub fn contains(&self, cell: HexCell) -> bool {
+        let column: i32 = cell.0 + cell.1.div_euclid(2);
+        cell.1 >= 0 && cell.1 < self.rows && column >= 0 && column < self.columns
+    }
+
+    pub fn cells(&self) -> Vec<HexCell> {
+        let mut cells: Vec<HexCell> = Vec::new();
+
+        for cur_row in 0..self.rows {
+            for cur_column in 0..self.columns {
+                cells.push((cur_column - cur_row.div_euclid(2), cur_row));
+            }
+        }
+
+        cells
+    }
+}
+
+// Grows `num_regions` contiguous regions of `cells_per_region` cells. Every
+// region after the first starts next to an existing one, so the regions are
+// connected to each other. Returns None if a region got boxed in.
+pub fn grow_regions<R: Rng + ?Sized>(
+    grid: &HexGrid,
+    num_regions: u32,
+    cells_per_region: u32,
+    rng: &mut R,
+) -> Option<Vec<Vec<HexCell>>> {
+    let mut owners: HashMap<HexCell, usize> = HashMap::new();
+    let mut regions: Vec<Vec<HexCell>> = Vec::new();
+
+    for cur_region in 0..num_regions as usize {
+        let seed: HexCell = if cur_region == 0 {
+            *grid.cells().choose(rng)?
+        } else {
+            let mut frontier: Vec<HexCell> = free_neighbors(grid, &owners, owners.keys());
+            frontier.sort_unstable();
+            *frontier.choose(rng)?
+        };
+
+        owners.insert(seed, cur_region);
+        let mut region: Vec<HexCell> = vec![seed];
+
+        while region.len() < cells_per_region as usize {
+            let mut frontier: Vec<HexCell> = free_neighbors(grid, &owners, region.iter());
+            frontier.sort_unstable();
+            let next_cell: HexCell = *frontier.choose(rng)?;
+
+            owners.insert(next_cell, cur_region);
+            region.push(next_cell);
+        }
+
+        regions.push(region);
+    }
+
+    Some(regions)
+}
+
+// Regions are neighbors when any of their cells share an edge
+pub fn region_neighbors(regions: &[Vec<HexCell>]) -> Vec<Vec<usize>> {
+    let mut owners: HashMap<HexCell, usize> = HashMap::new();
+    for (cur_region, cur_cells) in regions.iter().enumerate() {
+        for cur_cell in cur_cells {
+            owners.insert(*cur_cell, cur_region);
+        }
+    }
+
+    let mut neighbors: Vec<Vec<usize>> = vec![Vec::new(); regions.len()];
+    for (cur_region, cur_cells) in regions.iter().enumerate() {
+        for cur_cell in cur_cells {
+            for cur_neighbor in cell_neighbors(*cur_cell) {
+                if let Some(other_region) = owners.get(&cur_neighbor) {
+                    if *other_region != cur_region && !neighbors[cur_region].contains(other_region)
+                    {
+                        neighbors[cur_region].push(*other_region);
+                    }
+                }
+            }
+        }
+        neighbors[cur_region].sort_unstable();
+    }
+
+    neighbors
+}
+
+fn free_neighbors<'a, I: Iterator<Item = &'a HexCell>>(
+    grid: &HexGrid,
+    owners: &HashMap<HexCell, usize>,
+    cells: I,
+) -> Vec<HexCell> {
+    let mut free: Vec<HexCell> = Vec::new();
+
+    for cur_cell in cells {
+        for cur_neighbor in cell_neighbors(*cur_cell) {
+            if grid.contains(cur_neighbor)
+                && !owners.contains_key(&cur_neighbor)
+                && !free.contains(&cur_neighbor)
+            {
+                free.push(cur_neighbor);
+            }
+        }
+    }
+
+    free
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn grid_contains_its_cells() {
+        let grid = HexGrid {
+            columns: 4,
+            rows: 3,
+        };
+
+        assert_eq!(12, grid.cells().len());
+        for cur_cell in grid.cells() {
+            assert!(grid.contains(cur_cell));
+        }
+        assert!(!grid.contains((4, 0)));
+        assert!(!grid.contains((0, 3)));
+    }
+
+    #[test]
+    fn regions_are_disjoint_and_full_size() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let grid: HexGrid = HexGrid::for_cells(12 * 6);
+
+        let regions: Vec<Vec<HexCell>> = (0..20)
+            .find_map(|_attempt| grow_regions(&grid, 12, 6, &mut rng))
+            .unwrap();
+
+        let mut all_cells: Vec<HexCell> = regions.iter().flatten().copied().collect();
+        assert_eq!(72, all_cells.len());
+        all_cells.sort_unstable();
+        all_cells.dedup();
+        assert_eq!(72, all_cells.len());
+
+        for cur_region in &regions {
+            assert_eq!(6, cur_region.len());
+            for cur_cell in cur_region {
+                assert!(grid.contains(*cur_cell));
+            }
+        }
+    }
+
+    #[test]
+    fn region_neighbors_share_edges() {
+        let regions: Vec<Vec<HexCell>> = vec![vec![(0, 0)], vec![(1, 0)], vec![(3, 0)]];
+
+        assert_eq!(vec![vec![1], vec![0], vec![]], region_neighbors(&regions));
+    }
+}
@@ -0,0 +1,37 @@
+//! Rules engine for mini-risk, a DiceWars-style game of dice and territories.
+//!
+//! The `mini-risk` server binary is a thin network layer over [`server::GameServer`];
+//! bots, analytics and other tools can use the same types directly.
+
+pub mod analytics;
+pub mod combat;
+pub mod diplomacy;
+pub mod fairness;
+pub mod gameboard;
+pub mod hexmap;
+pub mod mcts;
+pub mod names;
+pub mod odds;
+pub mod planar;
+pub mod protocol;
+pub mod rules;
+pub mod server;
+pub mod strategy;
+pub mod topology;
+pub mod tournament;
+pub mod validation;
+
+pub use combat::{CombatMode, CombatRules, DiceRules, TiePolicy};
+pub use diplomacy::{EventKind, GameEvent, Pact, PactProposal};
+pub use gameboard::{
+    battle, BoardConfig, BoardMetadata, Continent, Gameboard, Handicap, MapStyle, Phase, Territory,
+    DEFAULT_MAX_DICE, NEUTRAL_OWNER,
+};
+pub use protocol::{ClientMessage, ProtocolError, ServerMessage};
+pub use rules::{
+    CapitalLoss, CapitalRules, CaptureRule, DiplomacyRules, FogOfWar, FortifyPath, FortifyRules,
+    ReinforcementMode, Rules, VictoryConditions,
+};
+pub use server::GameServer;
+pub use strategy::{Action, Strategy};
+pub use topology::TopologyOptions;
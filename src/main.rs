@@ -1,37 +1,237 @@
-use message_io::network::{NetEvent, Transport};
+use message_io::network::{Endpoint, NetEvent, Transport};
 use message_io::node::{self};
+use mini_risk::analytics;
+use mini_risk::strategy::{self, Strategy};
+use mini_risk::validation::{self, Diagnostic};
+use mini_risk::{
+    BoardConfig, CapitalLoss, CapitalRules, CaptureRule, ClientMessage, CombatMode, DiplomacyRules,
+    FogOfWar, FortifyPath, FortifyRules, GameServer, Gameboard, MapStyle, ReinforcementMode,
+    TiePolicy,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::HashMap;
 use std::env::{self};
+use std::fs;
+use std::process;
 
-mod gameboard;
-use crate::gameboard::Gameboard;
+const USAGE: &str = "Usage: mini-risk <num players> <num territories per player> <num dice per player> [--bots <random|greedy|mcts[:iterations|:time]>] [--humans <num human players>] [game options] [board options]
+       mini-risk --board <path> [--bots <...>] [--humans <num human players>] [game options]
+       mini-risk generate <num players> <num territories per player> <num dice per player> [--out <path>] [board options]
+       mini-risk validate <path>
+       mini-risk analyze <num players> <num territories per player> <num dice per player> [board options]
+       mini-risk analyze --board <path>
+
+Game options: [--combat <sum|classic|classic:blitz>] [--die-faces <2..20>] [--ties <defender|attacker|reroll>]
+              [--leave-one-behind <true|false>] [--fortify <neighbors|connected>] [--fortify-moves <n>]
+              [--capture <all|minimum|choose>] [--reinforcements <random|draft>]
+              [--capitals <eliminate|transfer>] [--capital-bonus <n>] [--capital-victory <true|false>]
+              [--turn-limit <rounds>] [--territory-share <percent>] [--score-limit <n>]
+              [--teams <team of each player, e.g. 0,0,1,1>] [--team-regions <true|false>]
+              [--pacts <longest pact in rounds>] [--pact-breaking <true|false>] [--fog <off|dice|ownership>]
+
+Board options: [--map <graph|planar|hex[:cells per territory]>] [--seed <n>] [--min-neighbors <n>] [--max-neighbors <n>]
+               [--average-degree <x>] [--bridges <n>] [--continents <n>] [--continent-links <n>] [--fairness <tolerance>]
+               [--territories-for <player:n,...>] [--dice-for <player:n,...>] [--last-seat-bonus <n>]
+               [--neutral-share <0..1>] [--neutral-dice <n>] [--names <true|false>]";
 
 fn main() {
     let args: Vec<String> = env::args().collect();
 
-    if args.len() < 4 {
-        panic!("Usage: mini-risk <num players> <num territories per player> <num dice per player>");
+    if args.len() > 1 && args[1] == "analyze" {
+        run_analyze(&args[2..]);
+        return;
     }
 
-    let num_players: u32 = args[1]
-        .parse()
-        .expect("num players must be a positive integer");
-    let num_territories_per_player: u32 = args[2]
-        .parse()
-        .expect("num player territories per player must be a positive integer");
-    let num_dice_per_player: u32 = args[3]
-        .parse()
-        .expect("num dice per player must be a positive integer");
+    if args.len() > 1 && args[1] == "validate" {
+        run_validate(&args[2..]);
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "generate" {
+        run_generate(&args[2..]);
+        return;
+    }
 
-    if num_dice_per_player < num_territories_per_player {
-        panic!("The number of dice per player must be greater than or equal to the number of territories 
-                per player");
+    // A saved board or map file replaces the three board sizes
+    let (mut gameboard, options): (Gameboard, HashMap<String, String>) =
+        if args.len() > 1 && args[1].starts_with("--") {
+            let options: HashMap<String, String> = parse_options(&args[1..]);
+            let board_path: &String = options.get("board").unwrap_or_else(|| panic!("{}", USAGE));
+            (load_board(board_path), options)
+        } else {
+            if args.len() < 4 {
+                panic!("{}", USAGE);
+            }
+            let (board_config, options): (BoardConfig, HashMap<String, String>) =
+                parse_board_args(&args[1..]);
+            (Gameboard::from_config(&board_config), options)
+        };
+    let num_players: u32 = gameboard.num_players;
+
+    if let Some(combat_name) = options.get("combat") {
+        gameboard.rules.combat = CombatMode::from_name(combat_name)
+            .unwrap_or_else(|| panic!("Unknown combat rules: {}", combat_name));
+    }
+    if let Some(die_faces) = options.get("die-faces") {
+        gameboard.rules.dice.die_faces = die_faces
+            .parse()
+            .expect("die faces must be a positive integer");
+    }
+    if let Some(ties_name) = options.get("ties") {
+        gameboard.rules.dice.ties = TiePolicy::from_name(ties_name)
+            .unwrap_or_else(|| panic!("Unknown tie policy: {}", ties_name));
+    }
+    if let Some(leave_one_behind) = options.get("leave-one-behind") {
+        gameboard.rules.dice.leave_one_behind = leave_one_behind
+            .parse()
+            .expect("leave-one-behind must be true or false");
+    }
+    if let Some(capture_name) = options.get("capture") {
+        gameboard.rules.capture = CaptureRule::from_name(capture_name)
+            .unwrap_or_else(|| panic!("Unknown capture rule: {}", capture_name));
+    }
+    if let Some(mode_name) = options.get("reinforcements") {
+        gameboard.rules.reinforcements = ReinforcementMode::from_name(mode_name)
+            .unwrap_or_else(|| panic!("Unknown reinforcement mode: {}", mode_name));
+    }
+    if let Some(turn_limit) = options.get("turn-limit") {
+        gameboard.rules.victory.turn_limit = Some(
+            turn_limit
+                .parse()
+                .expect("turn limit must be a positive number of rounds"),
+        );
+    }
+    if let Some(territory_percent) = options.get("territory-share") {
+        gameboard.rules.victory.territory_percent = Some(
+            territory_percent
+                .parse()
+                .expect("territory share must be a percentage between 1 and 100"),
+        );
+    }
+    if let Some(score_limit) = options.get("score-limit") {
+        gameboard.rules.victory.score_limit = Some(
+            score_limit
+                .parse()
+                .expect("score limit must be a positive integer"),
+        );
+    }
+    if options.contains_key("capitals")
+        || options.contains_key("capital-bonus")
+        || options.contains_key("capital-victory")
+    {
+        let mut capitals: CapitalRules = CapitalRules::default();
+        if let Some(loss_name) = options.get("capitals") {
+            capitals.on_loss = CapitalLoss::from_name(loss_name)
+                .unwrap_or_else(|| panic!("Unknown capital loss rule: {}", loss_name));
+        }
+        if let Some(defense_bonus) = options.get("capital-bonus") {
+            capitals.defense_bonus = defense_bonus
+                .parse()
+                .expect("capital bonus must be a non-negative integer");
+        }
+        if let Some(capital_victory) = options.get("capital-victory") {
+            capitals.capital_victory = capital_victory
+                .parse()
+                .expect("capital-victory must be true or false");
+        }
+        gameboard.rules.capitals = Some(capitals);
+        match gameboard.metadata.seed {
+            Some(seed) => gameboard.assign_capitals_with_rng(&mut StdRng::seed_from_u64(seed)),
+            None => gameboard.assign_capitals_with_rng(&mut rand::thread_rng()),
+        }
+    }
+    if options.contains_key("fortify") || options.contains_key("fortify-moves") {
+        let mut fortify: FortifyRules = FortifyRules::default();
+        if let Some(path_name) = options.get("fortify") {
+            fortify.path = FortifyPath::from_name(path_name)
+                .unwrap_or_else(|| panic!("Unknown fortify path: {}", path_name));
+        }
+        if let Some(max_moves) = options.get("fortify-moves") {
+            fortify.max_moves = max_moves
+                .parse()
+                .expect("fortify moves must be a positive integer");
+        }
+        gameboard.rules.fortify = Some(fortify);
+    }
+    if let Some(teams) = options.get("teams") {
+        gameboard.teams = teams
+            .split(',')
+            .map(|team| team.parse().expect("teams must be a list of team numbers"))
+            .collect();
+        if let Some(team) = gameboard
+            .teams
+            .iter()
+            .find(|team| **team >= gameboard.num_players)
+        {
+            panic!(
+                "Unknown team {}, teams are numbered from 0 to {}",
+                team,
+                gameboard.num_players - 1
+            );
+        }
+        if gameboard.teams.len() != gameboard.num_players as usize {
+            panic!(
+                "--teams needs a team for each of the {} players",
+                gameboard.num_players
+            );
+        }
+        if gameboard.num_teams_in_play() < 2 {
+            panic!("--teams needs at least two teams with territory");
+        }
+    }
+    if options.contains_key("pacts") || options.contains_key("pact-breaking") {
+        let mut diplomacy: DiplomacyRules = DiplomacyRules::default();
+        if let Some(max_turns) = options.get("pacts") {
+            diplomacy.max_turns = max_turns
+                .parse()
+                .expect("pacts must be a positive number of rounds");
+        }
+        if let Some(allow_breaking) = options.get("pact-breaking") {
+            diplomacy.allow_breaking = allow_breaking
+                .parse()
+                .expect("pact-breaking must be true or false");
+        }
+        gameboard.rules.diplomacy = Some(diplomacy);
+    }
+    if let Some(fog_name) = options.get("fog") {
+        gameboard.rules.fog = FogOfWar::from_name(fog_name)
+            .unwrap_or_else(|| panic!("Unknown fog of war: {}", fog_name));
+    }
+    if let Some(team_regions) = options.get("team-regions") {
+        gameboard.rules.team_regions = team_regions
+            .parse()
+            .expect("team-regions must be true or false");
+    }
+    if let Err(message) = gameboard.rules.validate() {
+        panic!("Invalid rules: {}", message);
     }
 
-    let mut gameboard: Gameboard =
-        Gameboard::new(num_players, num_territories_per_player, num_dice_per_player);
+    let mut num_humans: u32 = num_players;
+    let mut bots: HashMap<u32, Box<dyn Strategy>> = HashMap::new();
+    if let Some(bot_name) = options.get("bots") {
+        num_humans = match options.get("humans") {
+            Some(humans) => humans
+                .parse()
+                .expect("num human players must be a non-negative integer"),
+            None => 1,
+        };
+
+        if num_humans > num_players {
+            panic!("The number of human players cannot exceed the number of players");
+        }
+
+        for cur_seat in num_humans..num_players {
+            let bot = strategy::from_name(bot_name)
+                .unwrap_or_else(|| panic!("Unknown bot strategy: {}", bot_name));
+            bots.insert(cur_seat, bot);
+        }
+    }
 
     print!("{:#?}", gameboard);
 
+    let mut server: GameServer<Endpoint> = GameServer::new(gameboard, num_humans, bots);
+
     let (handler, listener) = node::split::<()>();
     handler
         .network()
@@ -43,44 +243,248 @@ fn main() {
         NetEvent::Accepted(_endpoint, _listener) => println!("Client connected"),
         NetEvent::Message(endpoint, data) => {
             let incoming_message: String = String::from_utf8(data.to_vec()).unwrap();
-            println!("Received: {:?}", String::from_utf8(data.to_vec()));
-            let split_message: Vec<&str> = incoming_message.split(";").collect();
-
-            match split_message[0] {
-                "Connect" => {
-                    println!("Got a Connect message");
-                    let gameboard_msg = create_gameboard_message(&gameboard);
-                    println!("Sending: {:?}", gameboard_msg);
-                    handler.network().send(endpoint, gameboard_msg.as_bytes());
-                }
-                "Attack" => {
-                    println!("Got an Attack message");
-                    if split_message.len() >= 3 {
-                        let attack_source: u32 = split_message[1].parse::<u32>().unwrap();
-                        let attack_target: u32 = split_message[2].parse::<u32>().unwrap();
-                        if gameboard.can_attack(attack_source, attack_target) {
-                            gameboard.attack(attack_source, attack_target);
-                            let gameboard_msg = create_gameboard_message(&gameboard);
-                            println!("Sending: {:?}", gameboard_msg);
-                            handler.network().send(endpoint, gameboard_msg.as_bytes());
-                        } else {
-                            // TODO: reply with error
-                        }
-                    } else {
-                        println!("Malformed Attack message");
+            println!("Received: {:?}", incoming_message);
+
+            match ClientMessage::parse(&incoming_message) {
+                Ok(message) => {
+                    for (cur_client, cur_reply) in server.handle_message(endpoint, message) {
+                        let reply_msg: String = cur_reply.encode();
+                        println!("Sending: {:?}", reply_msg);
+                        handler.network().send(cur_client, reply_msg.as_bytes());
                     }
                 }
-                _ => println!("Got an unknown message"),
+                Err(err) => println!("{}", err),
             }
         }
-        NetEvent::Disconnected(_endpoint) => println!("Client disconnected"),
+        NetEvent::Disconnected(endpoint) => {
+            println!("Client disconnected");
+            server.disconnect(endpoint);
+        }
     });
 }
 
-fn create_gameboard_message(gameboard: &Gameboard) -> String {
-    let gameboard_json = serde_json::to_string_pretty(&gameboard).unwrap();
-    let mut gameboard_msg: String = "Gameboard;".to_owned();
-    gameboard_msg.push_str(&gameboard_json.to_owned());
+fn read_board(path: &str) -> Gameboard {
+    let board_json: String =
+        fs::read_to_string(path).unwrap_or_else(|err| panic!("Unable to read {}: {}", path, err));
+
+    Gameboard::from_json(&board_json)
+        .unwrap_or_else(|err| panic!("Invalid board {}: {}", path, err))
+}
+
+fn load_board(path: &str) -> Gameboard {
+    let gameboard: Gameboard = read_board(path);
+
+    let diagnostics: Vec<Diagnostic> = validation::validate(&gameboard);
+    if !diagnostics.is_empty() {
+        for cur_diagnostic in &diagnostics {
+            println!("{}", cur_diagnostic);
+        }
+        panic!(
+            "{} has {} problems, run `mini-risk validate {}` for details",
+            path,
+            diagnostics.len(),
+            path
+        );
+    }
+
+    gameboard
+}
+
+fn run_analyze(args: &[String]) {
+    let gameboard: Gameboard = if args.first().is_some_and(|arg| arg.starts_with("--")) {
+        let options: HashMap<String, String> = parse_options(args);
+        let board_path: &String = options.get("board").unwrap_or_else(|| panic!("{}", USAGE));
+        read_board(board_path)
+    } else {
+        if args.len() < 3 {
+            panic!("{}", USAGE);
+        }
+        let (board_config, _options): (BoardConfig, HashMap<String, String>) =
+            parse_board_args(args);
+        Gameboard::from_config(&board_config)
+    };
+
+    print!("{}", analytics::analyze(&gameboard).to_text());
+}
+
+fn run_validate(args: &[String]) {
+    if args.len() != 1 {
+        panic!("Usage: mini-risk validate <path>");
+    }
+
+    let gameboard: Gameboard = read_board(&args[0]);
+    let diagnostics: Vec<Diagnostic> = validation::validate(&gameboard);
+
+    if diagnostics.is_empty() {
+        println!("{}: no problems found", args[0]);
+        return;
+    }
+
+    for cur_diagnostic in &diagnostics {
+        println!("{}: {}", args[0], cur_diagnostic);
+    }
+    println!("{} problems found", diagnostics.len());
+    process::exit(1);
+}
+
+fn run_generate(args: &[String]) {
+    if args.len() < 3 {
+        panic!("Usage: mini-risk generate <num players> <num territories per player> <num dice per player> [--out <path>] [board options]");
+    }
+
+    let (board_config, options): (BoardConfig, HashMap<String, String>) = parse_board_args(args);
+    let gameboard: Gameboard = Gameboard::from_config(&board_config);
+
+    match options.get("out") {
+        Some(out_path) => {
+            fs::write(out_path, gameboard.to_json()).expect("Unable to write the board");
+            println!("Wrote board to {}", out_path);
+        }
+        None => println!("{}", gameboard.to_json()),
+    }
+}
+
+// The three positional board sizes followed by `--key value` options
+fn parse_board_args(args: &[String]) -> (BoardConfig, HashMap<String, String>) {
+    let num_players: u32 = args[0]
+        .parse()
+        .expect("num players must be a positive integer");
+    let num_territories_per_player: u32 = args[1]
+        .parse()
+        .expect("num player territories per player must be a positive integer");
+    let num_dice_per_player: u32 = args[2]
+        .parse()
+        .expect("num dice per player must be a positive integer");
+
+    let options: HashMap<String, String> = parse_options(&args[3..]);
+
+    let mut board_config: BoardConfig =
+        BoardConfig::new(num_players, num_territories_per_player, num_dice_per_player);
+    if let Some(map_name) = options.get("map") {
+        board_config.map_style = MapStyle::from_name(map_name)
+            .unwrap_or_else(|| panic!("Unknown map style: {}", map_name));
+    }
+    if let Some(seed) = options.get("seed") {
+        board_config.seed = Some(seed.parse().expect("seed must be a non-negative integer"));
+    }
+    if let Some(min_neighbors) = options.get("min-neighbors") {
+        board_config.topology.min_neighbors = min_neighbors
+            .parse()
+            .expect("min neighbors must be a non-negative integer");
+    }
+    if let Some(max_neighbors) = options.get("max-neighbors") {
+        board_config.topology.max_neighbors = Some(
+            max_neighbors
+                .parse()
+                .expect("max neighbors must be a positive integer"),
+        );
+    }
+    if let Some(average_degree) = options.get("average-degree") {
+        board_config.topology.average_degree = average_degree
+            .parse()
+            .expect("average degree must be a number");
+    }
+    if let Some(num_bridges) = options.get("bridges") {
+        board_config.topology.num_bridges = Some(
+            num_bridges
+                .parse()
+                .expect("num bridges must be a non-negative integer"),
+        );
+    }
+    if let Some(num_continents) = options.get("continents") {
+        board_config.topology.num_continents = num_continents
+            .parse()
+            .expect("num continents must be a positive integer");
+    }
+    if let Some(continent_links) = options.get("continent-links") {
+        board_config.topology.continent_links = continent_links
+            .parse()
+            .expect("continent links must be a positive integer");
+    }
+
+    if let Some(territories_for) = options.get("territories-for") {
+        for (cur_player_id, cur_count) in parse_player_counts(territories_for) {
+            board_config
+                .handicaps
+                .entry(cur_player_id)
+                .or_default()
+                .num_territories = Some(cur_count);
+        }
+    }
+    if let Some(dice_for) = options.get("dice-for") {
+        for (cur_player_id, cur_count) in parse_player_counts(dice_for) {
+            board_config
+                .handicaps
+                .entry(cur_player_id)
+                .or_default()
+                .num_dice = Some(cur_count);
+        }
+    }
+    if let Some(bonus_dice) = options.get("last-seat-bonus") {
+        board_config.last_seat_bonus_dice = bonus_dice
+            .parse()
+            .expect("last seat bonus must be a non-negative integer");
+    }
+    if let Some(neutral_share) = options.get("neutral-share") {
+        board_config.neutral_share = neutral_share
+            .parse()
+            .expect("neutral share must be a number between 0 and 1");
+    }
+    if let Some(neutral_dice) = options.get("neutral-dice") {
+        board_config.neutral_dice = neutral_dice
+            .parse()
+            .expect("neutral dice must be a positive integer");
+    }
+    if let Some(name_territories) = options.get("names") {
+        board_config.name_territories = name_territories
+            .parse()
+            .expect("names must be true or false");
+    }
+    if let Some(tolerance) = options.get("fairness") {
+        board_config.fairness_tolerance = Some(
+            tolerance
+                .parse()
+                .expect("fairness tolerance must be a non-negative number"),
+        );
+    }
+
+    if let Err(err) = board_config.validate() {
+        panic!("{}", err);
+    }
+
+    (board_config, options)
+}
+
+// `0:6,2:4` gives player 0 a count of 6 and player 2 a count of 4
+fn parse_player_counts(value: &str) -> Vec<(u32, u32)> {
+    value
+        .split(',')
+        .map(|cur_pair| {
+            let (player_id, count) = cur_pair
+                .split_once(':')
+                .unwrap_or_else(|| panic!("Expected <player>:<count>, got {}", cur_pair));
+            (
+                player_id
+                    .parse()
+                    .expect("player must be a non-negative integer"),
+                count.parse().expect("count must be a non-negative integer"),
+            )
+        })
+        .collect()
+}
+
+fn parse_options(args: &[String]) -> HashMap<String, String> {
+    let mut options: HashMap<String, String> = HashMap::new();
+
+    for cur_pair in args.chunks(2) {
+        let name: &str = cur_pair[0]
+            .strip_prefix("--")
+            .unwrap_or_else(|| panic!("Unexpected argument: {}", cur_pair[0]));
+        let value: &String = cur_pair
+            .get(1)
+            .unwrap_or_else(|| panic!("Missing value for --{}", name));
+        options.insert(name.to_owned(), value.to_owned());
+    }
 
-    return gameboard_msg;
+    options
 }
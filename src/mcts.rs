@@ -0,0 +1,341 @@
+use crate::gameboard::{Gameboard, NEUTRAL_OWNER};
+use crate::strategy::{Action, Strategy};
+use rand::prelude::SliceRandom;
+use rand::{Rng, RngCore};
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_ITERATIONS: u32 = 1000;
+const EXPLORATION: f64 = 1.4;
+const MAX_ROLLOUT_TURNS: u32 = 40;
+const TURN_DISCOUNT: f64 = 0.95;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchBudget {
+    Iterations(u32),
+    Time(Duration),
+}
+
+impl SearchBudget {
+    // A plain number is an iteration count; `ms` and `s` suffixes give wall-clock time per move
+    pub fn parse(budget: &str) -> Option<SearchBudget> {
+        if let Some(millis) = budget.strip_suffix("ms") {
+            return Some(SearchBudget::Time(Duration::from_millis(
+                millis.parse().ok()?,
+            )));
+        }
+
+        if let Some(secs) = budget.strip_suffix('s') {
+            return Some(SearchBudget::Time(Duration::from_secs(secs.parse().ok()?)));
+        }
+
+        Some(SearchBudget::Iterations(budget.parse().ok()?))
+    }
+}
+
+struct Node {
+    action: Option<Action>,
+    player_id: u32,
+    visits: u32,
+    total_reward: f64,
+    children: Vec<usize>,
+}
+
+/// Open-loop Monte Carlo tree search: every iteration replays the tree's actions
+/// on a fresh clone of the board, so each visit samples new battle outcomes.
+pub struct MctsStrategy {
+    budget: SearchBudget,
+}
+
+impl MctsStrategy {
+    pub fn new(budget: SearchBudget) -> MctsStrategy {
+        MctsStrategy { budget }
+    }
+
+    fn run_iteration(
+        &mut self,
+        root_board: &Gameboard,
+        tree: &mut Vec<Node>,
+        rng: &mut dyn RngCore,
+    ) {
+        let mut board: Gameboard = root_board.clone();
+        let mut path: Vec<usize> = vec![0];
+        let mut cur_node: usize = 0;
+
+        while board.winner().is_none() {
+            let player_id: u32 = board.current_player_id;
+            let actions: Vec<Action> = legal_actions(&board, player_id);
+
+            let untried: Vec<Action> = actions
+                .iter()
+                .filter(|action| {
+                    !tree[cur_node]
+                        .children
+                        .iter()
+                        .any(|child| tree[*child].action == Some(**action))
+                })
+                .copied()
+                .collect();
+
+            if let Some(action) = untried.choose(rng) {
+                apply_action(&mut board, *action, rng);
+                tree.push(Node {
+                    action: Some(*action),
+                    player_id,
+                    visits: 0,
+                    total_reward: 0.0,
+                    children: Vec::new(),
+                });
+                let new_node: usize = tree.len() - 1;
+                tree[cur_node].children.push(new_node);
+                path.push(new_node);
+                break;
+            }
+
+            let next_node: usize = select_child(tree, cur_node, &actions);
+            apply_action(&mut board, tree[next_node].action.unwrap(), rng);
+            path.push(next_node);
+            cur_node = next_node;
+        }
+
+        let rewards: Vec<f64> = rollout(&mut board, rng);
+
+        // Discounting by game length makes the search prefer winning sooner over winning eventually
+        let discount: f64 = TURN_DISCOUNT.powi((board.turn_number - root_board.turn_number) as i32);
+
+        for cur_node in path {
+            let node: &mut Node = &mut tree[cur_node];
+            node.visits += 1;
+            node.total_reward += discount * rewards[node.player_id as usize];
+        }
+    }
+}
+
+impl Strategy for MctsStrategy {
+    fn name(&self) -> &str {
+        "mcts"
+    }
+
+    fn next_action(
+        &mut self,
+        gameboard: &Gameboard,
+        player_id: u32,
+        rng: &mut dyn RngCore,
+    ) -> Action {
+        let actions: Vec<Action> = legal_actions(gameboard, player_id);
+        if actions.len() == 1 || gameboard.current_player_id != player_id {
+            return Action::EndTurn;
+        }
+
+        let mut tree: Vec<Node> = vec![Node {
+            action: None,
+            player_id,
+            visits: 0,
+            total_reward: 0.0,
+            children: Vec::new(),
+        }];
+
+        let start: Instant = Instant::now();
+        let mut iterations: u32 = 0;
+        loop {
+            let budget_spent: bool = match self.budget {
+                SearchBudget::Iterations(max_iterations) => iterations >= max_iterations,
+                SearchBudget::Time(max_time) => start.elapsed() >= max_time,
+            };
+            if budget_spent && iterations > 0 {
+                break;
+            }
+
+            self.run_iteration(gameboard, &mut tree, rng);
+            iterations += 1;
+        }
+
+        tree[0]
+            .children
+            .iter()
+            .max_by_key(|child| tree[**child].visits)
+            .and_then(|child| tree[*child].action)
+            .unwrap_or(Action::EndTurn)
+    }
+}
+
+fn legal_actions(gameboard: &Gameboard, player_id: u32) -> Vec<Action> {
+    let mut actions: Vec<Action> = gameboard
+        .legal_attacks(player_id)
+        .into_iter()
+        .map(|(from, to)| Action::Attack { from, to })
+        .collect();
+    actions.push(Action::EndTurn);
+
+    actions
+}
+
+fn apply_action<R: Rng + ?Sized>(gameboard: &mut Gameboard, action: Action, rng: &mut R) {
+    match action {
+        Action::Attack { from, to } => gameboard.attack_with_rng(from, to, rng),
+        Action::EndTurn => {
+            gameboard.end_turn_with_rng(rng);
+            gameboard.place_at_random_with_rng(rng);
+        }
+    }
+}
+
+fn select_child(tree: &[Node], parent: usize, actions: &[Action]) -> usize {
+    let parent_visits: f64 = f64::from(tree[parent].visits.max(1));
+    let mut best_child: usize = tree[parent].children[0];
+    let mut best_score: f64 = f64::NEG_INFINITY;
+
+    for cur_child in &tree[parent].children {
+        let node: &Node = &tree[*cur_child];
+        if !actions.contains(&node.action.unwrap()) {
+            continue;
+        }
+
+        let visits: f64 = f64::from(node.visits.max(1));
+        let score: f64 =
+            node.total_reward / visits + EXPLORATION * (parent_visits.ln() / visits).sqrt();
+        if score > best_score {
+            best_score = score;
+            best_child = *cur_child;
+        }
+    }
+
+    best_child
+}
+
+// Plays favourable attacks until the game ends or the turn limit is hit, then
+// scores every player by their share of the board.
+fn rollout<R: Rng + ?Sized>(gameboard: &mut Gameboard, rng: &mut R) -> Vec<f64> {
+    let last_turn: u32 = gameboard.turn_number + MAX_ROLLOUT_TURNS;
+
+    while gameboard.winner().is_none() && gameboard.turn_number < last_turn {
+        let player_id: u32 = gameboard.current_player_id;
+        let favourable: Vec<(u32, u32)> = gameboard
+            .legal_attacks(player_id)
+            .into_iter()
+            .filter(|(from, to)| {
+                gameboard.territory_map[from].num_dice >= gameboard.territory_map[to].num_dice
+            })
+            .collect();
+
+        match favourable.choose(rng) {
+            Some((from, to)) => gameboard.attack_with_rng(*from, *to, rng),
+            None => {
+                gameboard.end_turn_with_rng(rng);
+                gameboard.place_at_random_with_rng(rng);
+            }
+        }
+    }
+
+    evaluate(gameboard)
+}
+
+fn evaluate(gameboard: &Gameboard) -> Vec<f64> {
+    let mut rewards: Vec<f64> = vec![0.0; gameboard.num_players as usize];
+
+    if let Some(winner) = gameboard.winner() {
+        for (cur_player_id, cur_reward) in rewards.iter_mut().enumerate() {
+            if gameboard.are_teammates(cur_player_id as u32, winner) {
+                *cur_reward = 1.0;
+            }
+        }
+        return rewards;
+    }
+
+    let total_territories: f64 = gameboard.territory_map.len() as f64;
+    let total_dice: f64 = gameboard
+        .territory_map
+        .values()
+        .map(|terr| f64::from(terr.num_dice))
+        .sum();
+
+    for cur_terr in gameboard.territory_map.values() {
+        if cur_terr.owner_id == NEUTRAL_OWNER {
+            continue;
+        }
+
+        let reward: &mut f64 = &mut rewards[cur_terr.owner_id as usize];
+        *reward += 0.5 / total_territories + 0.5 * f64::from(cur_terr.num_dice) / total_dice;
+    }
+
+    rewards
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameboard::Territory;
+    use rand::thread_rng;
+    use std::collections::HashMap;
+
+    #[test]
+    fn parse_budget() {
+        assert_eq!(
+            Some(SearchBudget::Iterations(500)),
+            SearchBudget::parse("500")
+        );
+        assert_eq!(
+            Some(SearchBudget::Time(Duration::from_millis(250))),
+            SearchBudget::parse("250ms")
+        );
+        assert_eq!(
+            Some(SearchBudget::Time(Duration::from_secs(2))),
+            SearchBudget::parse("2s")
+        );
+        assert_eq!(None, SearchBudget::parse("soon"));
+    }
+
+    #[test]
+    fn mcts_returns_legal_action() {
+        let gameboard: Gameboard = Gameboard::new(3, 3, 9);
+        let mut strategy = MctsStrategy::new(SearchBudget::Iterations(100));
+
+        match strategy.next_action(&gameboard, 0, &mut thread_rng()) {
+            Action::Attack { from, to } => {
+                assert!(gameboard.legal_attacks(0).contains(&(from, to)))
+            }
+            Action::EndTurn => {}
+        }
+    }
+
+    #[test]
+    fn mcts_takes_winning_attack() {
+        let terr_one = Territory {
+            id: 1,
+            num_dice: 8,
+            owner_id: 0,
+            neighbors: vec![2],
+            ..Default::default()
+        };
+
+        let terr_two = Territory {
+            id: 2,
+            num_dice: 1,
+            owner_id: 1,
+            neighbors: vec![1],
+            ..Default::default()
+        };
+
+        let mut territory_map: HashMap<u32, Territory> = HashMap::new();
+        territory_map.insert(1, terr_one);
+        territory_map.insert(2, terr_two);
+
+        let gameboard: Gameboard = Gameboard::from_territory_map(territory_map, 2);
+        let mut strategy = MctsStrategy::new(SearchBudget::Iterations(200));
+
+        assert_eq!(
+            Action::Attack { from: 1, to: 2 },
+            strategy.next_action(&gameboard, 0, &mut thread_rng())
+        );
+    }
+
+    #[test]
+    fn mcts_respects_time_budget() {
+        let gameboard: Gameboard = Gameboard::new(2, 4, 10);
+        let mut strategy = MctsStrategy::new(SearchBudget::Time(Duration::from_millis(50)));
+
+        let start: Instant = Instant::now();
+        strategy.next_action(&gameboard, 0, &mut thread_rng());
+
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,62 @@
+use rand::prelude::SliceRandom;
+use rand::Rng;
+use std::collections::HashSet;
+
+const PREFIXES: [&str; 16] = [
+    "Ar", "Bel", "Cor", "Dun", "El", "Fen", "Gal", "Hal", "Ir", "Kes", "Lor", "Mar", "Nor", "Os",
+    "Tir", "Val",
+];
+const MIDDLES: [&str; 8] = ["", "a", "e", "i", "o", "an", "en", "or"];
+const SUFFIXES: [&str; 12] = [
+    "dor", "mark", "heim", "wick", "ford", "gard", "moor", "vale", "ton", "crest", "reach", "holm",
+];
+
+// `num_names` distinct place names such as "Belenmoor". Falls back to
+// numbering once the syllable combinations run out.
+pub fn place_names<R: Rng + ?Sized>(num_names: usize, rng: &mut R) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(num_names);
+    let mut used: HashSet<String> = HashSet::new();
+    let max_attempts: usize = PREFIXES.len() * MIDDLES.len() * SUFFIXES.len();
+
+    while names.len() < num_names {
+        let mut name: String = String::new();
+
+        for _cur_attempt in 0..max_attempts {
+            let candidate: String = format!(
+                "{}{}{}",
+                PREFIXES.choose(rng).unwrap(),
+                MIDDLES.choose(rng).unwrap(),
+                SUFFIXES.choose(rng).unwrap()
+            );
+            if !used.contains(&candidate) {
+                name = candidate;
+                break;
+            }
+        }
+
+        if name.is_empty() {
+            name = format!("Territory {}", names.len());
+        }
+
+        used.insert(name.clone());
+        names.push(name);
+    }
+
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn names_are_distinct() {
+        let names: Vec<String> = place_names(200, &mut StdRng::seed_from_u64(8));
+
+        let distinct: HashSet<&String> = names.iter().collect();
+        assert_eq!(200, distinct.len());
+        assert!(names.iter().all(|name| !name.is_empty()));
+    }
+}
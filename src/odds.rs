@@ -0,0 +1,215 @@
+use crate::combat::{DiceRules, TiePolicy};
+use crate::gameboard::DEFAULT_MAX_DICE;
+use std::sync::OnceLock;
+
+static ODDS_TABLE: OnceLock<OddsTable> = OnceLock::new();
+
+// Probability of every possible sum when rolling `num_rolled` dice with
+// `die_faces` faces, indexed by the sum
+pub fn sum_distribution(num_rolled: u32, die_faces: u32) -> Vec<f64> {
+    let die_faces: usize = die_faces as usize;
+    let mut distribution: Vec<f64> = vec![1.0];
+
+    for _cur_die in 0..num_rolled {
+        let mut next: Vec<f64> = vec![0.0; distribution.len() + die_faces];
+
+        for (cur_sum, cur_probability) in distribution.iter().enumerate() {
+            for cur_face in 1..=die_faces {
+                next[cur_sum + cur_face] += cur_probability / die_faces as f64;
+            }
+        }
+
+        distribution = next;
+    }
+
+    distribution
+}
+
+// Mirrors `combat::sum_battle`
+fn compute_success_probability(attacker_dice: u32, defender_dice: u32, dice: &DiceRules) -> f64 {
+    let num_attack_rolls: u32 = dice.num_rolled(attacker_dice);
+    if num_attack_rolls == 0 {
+        return 0.0;
+    }
+
+    let attacker_sums: Vec<f64> = sum_distribution(num_attack_rolls, dice.die_faces);
+    let defender_sums: Vec<f64> = sum_distribution(dice.num_rolled(defender_dice), dice.die_faces);
+
+    let mut defender_cumulative: Vec<f64> = Vec::with_capacity(defender_sums.len());
+    let mut running_total: f64 = 0.0;
+    for cur_probability in &defender_sums {
+        running_total += cur_probability;
+        defender_cumulative.push(running_total);
+    }
+
+    let mut success: f64 = 0.0;
+    let mut tie: f64 = 0.0;
+    for (cur_sum, cur_probability) in attacker_sums.iter().enumerate().skip(1) {
+        let defender_below: f64 =
+            defender_cumulative[(cur_sum - 1).min(defender_cumulative.len() - 1)];
+        success += cur_probability * defender_below;
+        tie += cur_probability * defender_sums.get(cur_sum).copied().unwrap_or(0.0);
+    }
+
+    match dice.ties {
+        TiePolicy::Defender => success,
+        TiePolicy::Attacker => success + tie,
+        // Rerolling until someone wins only keeps the decided rolls
+        TiePolicy::Reroll => success / (1.0 - tie),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OddsTable {
+    max_dice: u32,
+    probabilities: Vec<Vec<f64>>,
+}
+
+impl OddsTable {
+    pub fn new(max_dice: u32) -> OddsTable {
+        OddsTable::with_dice(max_dice, &DiceRules::default())
+    }
+
+    pub fn with_dice(max_dice: u32, dice: &DiceRules) -> OddsTable {
+        let mut probabilities: Vec<Vec<f64>> = Vec::new();
+
+        for cur_attacker in 0..=max_dice {
+            let row: Vec<f64> = (0..=max_dice)
+                .map(|cur_defender| compute_success_probability(cur_attacker, cur_defender, dice))
+                .collect();
+            probabilities.push(row);
+        }
+
+        OddsTable {
+            max_dice,
+            probabilities,
+        }
+    }
+
+    pub fn get(&self, attacker_dice: u32, defender_dice: u32) -> Option<f64> {
+        if attacker_dice > self.max_dice || defender_dice > self.max_dice {
+            return None;
+        }
+
+        Some(self.probabilities[attacker_dice as usize][defender_dice as usize])
+    }
+}
+
+// Chance that a territory holding `attacker_dice` captures a neighbor holding `defender_dice`
+pub fn attack_success_probability(attacker_dice: u32, defender_dice: u32) -> f64 {
+    sum_success_probability(attacker_dice, defender_dice, &DiceRules::default())
+}
+
+// Same as `attack_success_probability` for any dice. Only the default dice are cached.
+pub fn sum_success_probability(attacker_dice: u32, defender_dice: u32, dice: &DiceRules) -> f64 {
+    if *dice != DiceRules::default() {
+        return compute_success_probability(attacker_dice, defender_dice, dice);
+    }
+
+    ODDS_TABLE
+        .get_or_init(|| OddsTable::new(DEFAULT_MAX_DICE))
+        .get(attacker_dice, defender_dice)
+        .unwrap_or_else(|| compute_success_probability(attacker_dice, defender_dice, dice))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(expected: f64, actual: f64) {
+        assert!(
+            (expected - actual).abs() < 1e-9,
+            "expected {} but got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn sum_distribution_two_dice() {
+        let distribution: Vec<f64> = sum_distribution(2, 6);
+
+        assert_eq!(13, distribution.len());
+        assert_close(0.0, distribution[1]);
+        assert_close(1.0 / 36.0, distribution[2]);
+        assert_close(6.0 / 36.0, distribution[7]);
+        assert_close(1.0, distribution.iter().sum());
+    }
+
+    #[test]
+    fn single_die_cannot_attack() {
+        assert_close(0.0, attack_success_probability(1, 1));
+        assert_close(0.0, attack_success_probability(1, 8));
+    }
+
+    #[test]
+    fn undefended_territory_always_falls() {
+        assert_close(1.0, attack_success_probability(2, 1));
+        assert_close(1.0, attack_success_probability(8, 1));
+    }
+
+    #[test]
+    fn one_die_each() {
+        // 15 of the 36 rolls have the attacker strictly ahead
+        assert_close(15.0 / 36.0, attack_success_probability(2, 2));
+    }
+
+    #[test]
+    fn table_matches_direct_computation() {
+        let table: OddsTable = OddsTable::new(4);
+
+        assert_close(
+            compute_success_probability(4, 3, &DiceRules::default()),
+            table.get(4, 3).unwrap(),
+        );
+        assert_eq!(None, table.get(5, 3));
+        assert_close(
+            compute_success_probability(12, 10, &DiceRules::default()),
+            attack_success_probability(12, 10),
+        );
+    }
+
+    #[test]
+    fn tie_policies() {
+        let with_ties = |ties: TiePolicy| -> DiceRules {
+            DiceRules {
+                ties,
+                ..DiceRules::default()
+            }
+        };
+
+        // One die each, 6 of the 36 rolls are ties
+        assert_close(
+            21.0 / 36.0,
+            sum_success_probability(2, 2, &with_ties(TiePolicy::Attacker)),
+        );
+        assert_close(
+            0.5,
+            sum_success_probability(2, 2, &with_ties(TiePolicy::Reroll)),
+        );
+    }
+
+    #[test]
+    fn other_dice() {
+        let dice = DiceRules {
+            die_faces: 4,
+            leave_one_behind: false,
+            ..DiceRules::default()
+        };
+
+        // Every die rolls, one d4 against one d4 is ahead in 6 of 16 rolls
+        assert_close(6.0 / 16.0, sum_success_probability(1, 1, &dice));
+        assert_close(1.0, sum_success_probability(5, 1, &dice));
+        assert!(sum_success_probability(3, 2, &dice) < 1.0);
+    }
+
+    #[test]
+    fn more_dice_means_better_odds() {
+        for cur_defender in 1..DEFAULT_MAX_DICE {
+            assert!(
+                attack_success_probability(cur_defender + 1, cur_defender)
+                    < attack_success_probability(cur_defender + 2, cur_defender)
+            );
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use rand::Rng;
+
+/// A position on the map. Generated maps fit in the unit square, `(0, 0)` to `(1, 1)`.
+pub type Point = (f64, f64);
+
+const SEED_CANDIDATES: u32 = 10;
+const EPSILON: f64 = 1e-9;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cell {
+    pub seed: Point,
+    pub polygon: Vec<Point>,
+    pub centroid: Point,
+    pub neighbors: Vec<usize>,
+}
+
+// Best-candidate sampling: each seed is the candidate farthest from the seeds
+// placed so far, which spreads territories out without a rigid grid
+pub fn place_seeds<R: Rng + ?Sized>(num_seeds: usize, rng: &mut R) -> Vec<Point> {
+    let mut seeds: Vec<Point> = Vec::with_capacity(num_seeds);
+
+    while seeds.len() < num_seeds {
+        let mut best_candidate: Point = (rng.gen(), rng.gen());
+        let mut best_distance: f64 = nearest_distance(&seeds, best_candidate);
+
+        for _cur_candidate in 1..SEED_CANDIDATES {
+            let candidate: Point = (rng.gen(), rng.gen());
+            let distance: f64 = nearest_distance(&seeds, candidate);
+            if distance > best_distance {
+                best_candidate = candidate;
+                best_distance = distance;
+            }
+        }
+
+        seeds.push(best_candidate);
+    }
+
+    seeds
+}
+
+// Voronoi cells of `seeds` clipped to the unit square. Two cells are neighbors
+// when they share an edge, which makes the adjacency the (planar) Delaunay graph.
+pub fn voronoi_cells(seeds: &[Point]) -> Vec<Cell> {
+    let mut cells: Vec<Cell> = Vec::with_capacity(seeds.len());
+
+    for (cur_index, cur_seed) in seeds.iter().enumerate() {
+        // Each vertex carries the site whose bisector forms the edge leaving it,
+        // or None for the map border
+        let mut polygon: Vec<(Point, Option<usize>)> = vec![
+            ((0.0, 0.0), None),
+            ((1.0, 0.0), None),
+            ((1.0, 1.0), None),
+            ((0.0, 1.0), None),
+        ];
+
+        for (other_index, other_seed) in seeds.iter().enumerate() {
+            if other_index != cur_index {
+                polygon = clip_to_half_plane(&polygon, *cur_seed, *other_seed, other_index);
+            }
+        }
+
+        let mut neighbors: Vec<usize> = Vec::new();
+        for (vertex_index, (vertex, edge_site)) in polygon.iter().enumerate() {
+            let next_vertex: Point = polygon[(vertex_index + 1) % polygon.len()].0;
+            if let Some(site) = edge_site {
+                if distance(*vertex, next_vertex) > EPSILON && !neighbors.contains(site) {
+                    neighbors.push(*site);
+                }
+            }
+        }
+
+        let vertices: Vec<Point> = polygon.iter().map(|(vertex, _)| *vertex).collect();
+        cells.push(Cell {
+            seed: *cur_seed,
+            centroid: centroid(&vertices),
+            polygon: vertices,
+            neighbors,
+        });
+    }
+
+    // Floating point can drop a vanishingly short shared edge from only one side
+    for cur_index in 0..cells.len() {
+        for neighbor_index in cells[cur_index].neighbors.clone() {
+            if !cells[neighbor_index].neighbors.contains(&cur_index) {
+                cells[neighbor_index].neighbors.push(cur_index);
+            }
+        }
+    }
+
+    cells
+}
+
+pub fn polygon_area(polygon: &[Point]) -> f64 {
+    let mut twice_area: f64 = 0.0;
+
+    for (cur_index, cur_vertex) in polygon.iter().enumerate() {
+        let next_vertex: Point = polygon[(cur_index + 1) % polygon.len()];
+        twice_area += cur_vertex.0 * next_vertex.1 - next_vertex.0 * cur_vertex.1;
+    }
+
+    twice_area.abs() / 2.0
+}
+
+fn centroid(polygon: &[Point]) -> Point {
+    let mut twice_area: f64 = 0.0;
+    let mut x_sum: f64 = 0.0;
+    let mut y_sum: f64 = 0.0;
+
+    for (cur_index, cur_vertex) in polygon.iter().enumerate() {
+        let next_vertex: Point = polygon[(cur_index + 1) % polygon.len()];
+        let cross: f64 = cur_vertex.0 * next_vertex.1 - next_vertex.0 * cur_vertex.1;
+        twice_area += cross;
+        x_sum += (cur_vertex.0 + next_vertex.0) * cross;
+        y_sum += (cur_vertex.1 + next_vertex.1) * cross;
+    }
+
+    if twice_area.abs() < EPSILON {
+        return polygon[0];
+    }
+
+    (x_sum / (3.0 * twice_area), y_sum / (3.0 * twice_area))
+}
+
+// Sutherland-Hodgman against the half-plane of points at least as close to
+// `seed` as to `other_seed`
+fn clip_to_half_plane(
+    polygon: &[(Point, Option<usize>)],
+    seed: Point,
+    other_seed: Point,
+    other_index: usize,
+) -> Vec<(Point, Option<usize>)> {
+    let normal: Point = (other_seed.0 - seed.0, other_seed.1 - seed.1);
+    let offset: f64 = (other_seed.0 * other_seed.0 + other_seed.1 * other_seed.1
+        - seed.0 * seed.0
+        - seed.1 * seed.1)
+        / 2.0;
+    let side = |point: Point| normal.0 * point.0 + normal.1 * point.1 - offset;
+
+    let mut clipped: Vec<(Point, Option<usize>)> = Vec::with_capacity(polygon.len() + 1);
+
+    for (cur_index, (cur_vertex, cur_edge_site)) in polygon.iter().enumerate() {
+        let next_vertex: Point = polygon[(cur_index + 1) % polygon.len()].0;
+        let cur_side: f64 = side(*cur_vertex);
+        let next_side: f64 = side(next_vertex);
+        let cur_inside: bool = cur_side <= EPSILON;
+        let next_inside: bool = next_side <= EPSILON;
+
+        if cur_inside {
+            clipped.push((*cur_vertex, *cur_edge_site));
+        }
+
+        if cur_inside != next_inside {
+            let t: f64 = cur_side / (cur_side - next_side);
+            let crossing: Point = (
+                cur_vertex.0 + t * (next_vertex.0 - cur_vertex.0),
+                cur_vertex.1 + t * (next_vertex.1 - cur_vertex.1),
+            );
+
+            if cur_inside {
+                clipped.push((crossing, Some(other_index)));
+            } else {
+                clipped.push((crossing, *cur_edge_site));
+            }
+        }
+    }
+
+    clipped
+}
+
+fn nearest_distance(points: &[Point], point: Point) -> f64 {
+    points
+        .iter()
+        .map(|other| distance(*other, point))
+        .fold(f64::INFINITY, f64::min)
+}
+
+fn distance(first: Point, second: Point) -> f64 {
+    ((first.0 - second.0).powi(2) + (first.1 - second.1).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn two_seeds_split_the_square() {
+        let cells: Vec<Cell> = voronoi_cells(&[(0.25, 0.5), (0.75, 0.5)]);
+
+        assert_eq!(vec![1], cells[0].neighbors);
+        assert_eq!(vec![0], cells[1].neighbors);
+        assert!((polygon_area(&cells[0].polygon) - 0.5).abs() < 1e-9);
+        assert!((cells[0].centroid.0 - 0.25).abs() < 1e-9);
+        assert!((cells[0].centroid.1 - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn four_seeds_neighbors_share_edges() {
+        // The middle Voronoi vertex is (0.5, 0.5), equidistant from the first three
+        // seeds, so the first and last seeds never touch
+        let cells: Vec<Cell> =
+            voronoi_cells(&[(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.8, 0.8)]);
+
+        let mut first_neighbors: Vec<usize> = cells[0].neighbors.clone();
+        first_neighbors.sort_unstable();
+        assert_eq!(vec![1, 2], first_neighbors);
+
+        let mut second_neighbors: Vec<usize> = cells[1].neighbors.clone();
+        second_neighbors.sort_unstable();
+        assert_eq!(vec![0, 2, 3], second_neighbors);
+    }
+
+    #[test]
+    fn cells_tile_the_square() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let seeds: Vec<Point> = place_seeds(40, &mut rng);
+        let cells: Vec<Cell> = voronoi_cells(&seeds);
+
+        let total_area: f64 = cells.iter().map(|cell| polygon_area(&cell.polygon)).sum();
+        assert!((total_area - 1.0).abs() < 1e-6);
+
+        for (cur_index, cur_cell) in cells.iter().enumerate() {
+            assert!(!cur_cell.neighbors.is_empty());
+            for cur_neighbor in &cur_cell.neighbors {
+                assert!(cells[*cur_neighbor].neighbors.contains(&cur_index));
+            }
+        }
+    }
+}
@@ -0,0 +1,390 @@
+use crate::diplomacy::{EventKind, GameEvent};
+use crate::gameboard::Gameboard;
+use std::fmt;
+
+/// Messages sent by clients. On the wire they are `;`-separated text, e.g. `Attack;3;7`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientMessage {
+    Connect,
+    /// `Attack;from;to[;count]`, `count` is how many dice advance on a capture
+    Attack {
+        from: u32,
+        to: u32,
+        count: Option<u32>,
+    },
+    EndTurn,
+    Odds {
+        from: u32,
+        to: u32,
+    },
+    Move {
+        from: u32,
+        to: u32,
+        count: u32,
+    },
+    Place {
+        territory: u32,
+        count: u32,
+    },
+    Capital {
+        territory: u32,
+    },
+    /// `Team;team`, joins a team in the lobby
+    Team {
+        team: u32,
+    },
+    /// `Propose;partner;turns`, offers a non-aggression pact
+    Propose {
+        partner: u32,
+        turns: u32,
+    },
+    /// `Accept;proposer`
+    Accept {
+        proposer: u32,
+    },
+    /// `Break;partner`
+    Break {
+        partner: u32,
+    },
+}
+
+/// Messages sent by the server.
+#[derive(Debug, Clone)]
+pub enum ServerMessage {
+    Gameboard(Box<Gameboard>),
+    Odds {
+        from: u32,
+        to: u32,
+        probability: f64,
+    },
+    /// Dice `player_id` has to place before attacking
+    Reinforcements {
+        player_id: u32,
+        count: u32,
+    },
+    /// New entry of the event log, announced to everyone
+    Event(GameEvent),
+    /// Player the receiving client is seated as, sent when it connects
+    Seat {
+        player_id: u32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProtocolError {
+    UnknownCommand(String),
+    Malformed(String),
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProtocolError::UnknownCommand(command) => write!(f, "Unknown message: {}", command),
+            ProtocolError::Malformed(command) => write!(f, "Malformed {} message", command),
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+impl ClientMessage {
+    pub fn parse(message: &str) -> Result<ClientMessage, ProtocolError> {
+        let split_message: Vec<&str> = message.trim().split(';').collect();
+
+        match split_message[0] {
+            "Connect" => Ok(ClientMessage::Connect),
+            "EndTurn" => Ok(ClientMessage::EndTurn),
+            "Attack" => {
+                let (from, to) = parse_territory_pair(&split_message)?;
+                let count: Option<u32> = match split_message.get(3) {
+                    Some(count) => Some(
+                        count
+                            .parse()
+                            .map_err(|_| ProtocolError::Malformed(split_message[0].to_owned()))?,
+                    ),
+                    None => None,
+                };
+                Ok(ClientMessage::Attack { from, to, count })
+            }
+            "Odds" => {
+                let (from, to) = parse_territory_pair(&split_message)?;
+                Ok(ClientMessage::Odds { from, to })
+            }
+            "Move" => {
+                let (from, to) = parse_territory_pair(&split_message)?;
+                let count: u32 = parse_field(&split_message, 3)?;
+                Ok(ClientMessage::Move { from, to, count })
+            }
+            "Place" => {
+                let territory: u32 = parse_field(&split_message, 1)?;
+                let count: u32 = parse_field(&split_message, 2)?;
+                Ok(ClientMessage::Place { territory, count })
+            }
+            "Capital" => {
+                let territory: u32 = parse_field(&split_message, 1)?;
+                Ok(ClientMessage::Capital { territory })
+            }
+            "Team" => {
+                let team: u32 = parse_field(&split_message, 1)?;
+                Ok(ClientMessage::Team { team })
+            }
+            "Propose" => {
+                let partner: u32 = parse_field(&split_message, 1)?;
+                let turns: u32 = parse_field(&split_message, 2)?;
+                Ok(ClientMessage::Propose { partner, turns })
+            }
+            "Accept" => {
+                let proposer: u32 = parse_field(&split_message, 1)?;
+                Ok(ClientMessage::Accept { proposer })
+            }
+            "Break" => {
+                let partner: u32 = parse_field(&split_message, 1)?;
+                Ok(ClientMessage::Break { partner })
+            }
+            command => Err(ProtocolError::UnknownCommand(command.to_owned())),
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        match self {
+            ClientMessage::Connect => "Connect".to_owned(),
+            ClientMessage::Attack {
+                from,
+                to,
+                count: None,
+            } => format!("Attack;{};{}", from, to),
+            ClientMessage::Attack {
+                from,
+                to,
+                count: Some(count),
+            } => format!("Attack;{};{};{}", from, to, count),
+            ClientMessage::EndTurn => "EndTurn".to_owned(),
+            ClientMessage::Odds { from, to } => format!("Odds;{};{}", from, to),
+            ClientMessage::Move { from, to, count } => format!("Move;{};{};{}", from, to, count),
+            ClientMessage::Place { territory, count } => format!("Place;{};{}", territory, count),
+            ClientMessage::Capital { territory } => format!("Capital;{}", territory),
+            ClientMessage::Team { team } => format!("Team;{}", team),
+            ClientMessage::Propose { partner, turns } => format!("Propose;{};{}", partner, turns),
+            ClientMessage::Accept { proposer } => format!("Accept;{}", proposer),
+            ClientMessage::Break { partner } => format!("Break;{}", partner),
+        }
+    }
+}
+
+impl ServerMessage {
+    pub fn encode(&self) -> String {
+        match self {
+            ServerMessage::Gameboard(gameboard) => {
+                let gameboard_json = serde_json::to_string_pretty(gameboard).unwrap();
+                let mut gameboard_msg: String = "Gameboard;".to_owned();
+                gameboard_msg.push_str(&gameboard_json);
+
+                gameboard_msg
+            }
+            ServerMessage::Odds {
+                from,
+                to,
+                probability,
+            } => format!("Odds;{};{};{:.4}", from, to, probability),
+            ServerMessage::Reinforcements { player_id, count } => {
+                format!("Reinforcements;{};{}", player_id, count)
+            }
+            ServerMessage::Seat { player_id } => format!("Seat;{}", player_id),
+            // `Event;turn;kind;players...`
+            ServerMessage::Event(event) => {
+                let details: String = match event.kind {
+                    EventKind::PactProposed {
+                        proposer,
+                        partner,
+                        turns,
+                    } => format!("PactProposed;{};{};{}", proposer, partner, turns),
+                    EventKind::PactAccepted {
+                        proposer,
+                        partner,
+                        expires_on_turn,
+                    } => format!("PactAccepted;{};{};{}", proposer, partner, expires_on_turn),
+                    EventKind::PactBroken { breaker, partner } => {
+                        format!("PactBroken;{};{}", breaker, partner)
+                    }
+                    EventKind::PactExpired {
+                        first_player_id,
+                        second_player_id,
+                    } => format!("PactExpired;{};{}", first_player_id, second_player_id),
+                };
+                format!("Event;{};{}", event.turn_number, details)
+            }
+        }
+    }
+}
+
+fn parse_field(split_message: &[&str], index: usize) -> Result<u32, ProtocolError> {
+    split_message
+        .get(index)
+        .and_then(|field| field.parse().ok())
+        .ok_or_else(|| ProtocolError::Malformed(split_message[0].to_owned()))
+}
+
+fn parse_territory_pair(split_message: &[&str]) -> Result<(u32, u32), ProtocolError> {
+    let malformed = || ProtocolError::Malformed(split_message[0].to_owned());
+
+    if split_message.len() < 3 {
+        return Err(malformed());
+    }
+
+    let from: u32 = split_message[1].parse().map_err(|_| malformed())?;
+    let to: u32 = split_message[2].parse().map_err(|_| malformed())?;
+
+    Ok((from, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_client_messages() {
+        assert_eq!(Ok(ClientMessage::Connect), ClientMessage::parse("Connect"));
+        assert_eq!(Ok(ClientMessage::EndTurn), ClientMessage::parse("EndTurn"));
+        assert_eq!(
+            Ok(ClientMessage::Attack {
+                from: 3,
+                to: 7,
+                count: None
+            }),
+            ClientMessage::parse("Attack;3;7")
+        );
+        assert_eq!(
+            Ok(ClientMessage::Attack {
+                from: 3,
+                to: 7,
+                count: Some(2)
+            }),
+            ClientMessage::parse("Attack;3;7;2")
+        );
+        assert_eq!(
+            Ok(ClientMessage::Odds { from: 1, to: 2 }),
+            ClientMessage::parse("Odds;1;2")
+        );
+        assert_eq!(
+            Ok(ClientMessage::Move {
+                from: 1,
+                to: 2,
+                count: 3
+            }),
+            ClientMessage::parse("Move;1;2;3")
+        );
+    }
+
+    #[test]
+    fn parse_rejects_bad_messages() {
+        assert_eq!(
+            Err(ProtocolError::Malformed("Attack".to_owned())),
+            ClientMessage::parse("Attack;3")
+        );
+        assert_eq!(
+            Err(ProtocolError::Malformed("Attack".to_owned())),
+            ClientMessage::parse("Attack;3;7;all")
+        );
+        assert_eq!(
+            Err(ProtocolError::Malformed("Odds".to_owned())),
+            ClientMessage::parse("Odds;one;two")
+        );
+        assert_eq!(
+            Err(ProtocolError::Malformed("Move".to_owned())),
+            ClientMessage::parse("Move;1;2")
+        );
+        assert_eq!(
+            Err(ProtocolError::Malformed("Place".to_owned())),
+            ClientMessage::parse("Place;4")
+        );
+        assert_eq!(
+            Err(ProtocolError::Malformed("Team".to_owned())),
+            ClientMessage::parse("Team")
+        );
+        assert_eq!(
+            Err(ProtocolError::UnknownCommand("Surrender".to_owned())),
+            ClientMessage::parse("Surrender")
+        );
+    }
+
+    #[test]
+    fn client_messages_round_trip() {
+        let messages: Vec<ClientMessage> = vec![
+            ClientMessage::Connect,
+            ClientMessage::Attack {
+                from: 4,
+                to: 9,
+                count: None,
+            },
+            ClientMessage::Attack {
+                from: 4,
+                to: 9,
+                count: Some(3),
+            },
+            ClientMessage::EndTurn,
+            ClientMessage::Odds { from: 0, to: 1 },
+            ClientMessage::Move {
+                from: 2,
+                to: 5,
+                count: 1,
+            },
+            ClientMessage::Place {
+                territory: 6,
+                count: 2,
+            },
+            ClientMessage::Capital { territory: 3 },
+            ClientMessage::Team { team: 1 },
+            ClientMessage::Propose {
+                partner: 2,
+                turns: 3,
+            },
+            ClientMessage::Accept { proposer: 0 },
+            ClientMessage::Break { partner: 1 },
+        ];
+
+        for cur_message in messages {
+            assert_eq!(Ok(cur_message), ClientMessage::parse(&cur_message.encode()));
+        }
+    }
+
+    #[test]
+    fn encode_odds() {
+        let message = ServerMessage::Odds {
+            from: 1,
+            to: 2,
+            probability: 15.0 / 36.0,
+        };
+
+        assert_eq!("Odds;1;2;0.4167", message.encode());
+    }
+
+    #[test]
+    fn encode_reinforcements() {
+        let message = ServerMessage::Reinforcements {
+            player_id: 1,
+            count: 4,
+        };
+
+        assert_eq!("Reinforcements;1;4", message.encode());
+    }
+
+    #[test]
+    fn encode_seat() {
+        let message = ServerMessage::Seat { player_id: 2 };
+
+        assert_eq!("Seat;2", message.encode());
+    }
+
+    #[test]
+    fn encode_events() {
+        let message = ServerMessage::Event(GameEvent {
+            turn_number: 7,
+            kind: EventKind::PactBroken {
+                breaker: 2,
+                partner: 0,
+            },
+        });
+
+        assert_eq!("Event;7;PactBroken;2;0", message.encode());
+    }
+}
@@ -0,0 +1,296 @@
+use crate::combat::{CombatMode, CombatRules, DiceRules};
+use serde::{Deserialize, Serialize};
+
+/// Which owned territories dice can be moved between while fortifying.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FortifyPath {
+    /// Only directly to a neighbor
+    Neighbors,
+    /// Anywhere reachable through the player's own territories
+    #[default]
+    Connected,
+}
+
+impl FortifyPath {
+    pub fn from_name(name: &str) -> Option<FortifyPath> {
+        match name {
+            "neighbors" => Some(FortifyPath::Neighbors),
+            "connected" => Some(FortifyPath::Connected),
+            _ => None,
+        }
+    }
+}
+
+/// Moving dice between owned territories once a player is done attacking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FortifyRules {
+    pub path: FortifyPath,
+    /// Moves allowed per turn
+    pub max_moves: u32,
+}
+
+impl Default for FortifyRules {
+    fn default() -> FortifyRules {
+        FortifyRules {
+            path: FortifyPath::Connected,
+            max_moves: 1,
+        }
+    }
+}
+
+/// How many of the attacker's surviving dice advance into a captured territory.
+/// One die always stays behind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CaptureRule {
+    #[default]
+    MoveAll,
+    /// A single die advances
+    MoveMinimum,
+    /// The attacker names a count with the attack, everything advances otherwise
+    Choose,
+}
+
+impl CaptureRule {
+    pub fn from_name(name: &str) -> Option<CaptureRule> {
+        match name {
+            "all" => Some(CaptureRule::MoveAll),
+            "minimum" => Some(CaptureRule::MoveMinimum),
+            "choose" => Some(CaptureRule::Choose),
+            _ => None,
+        }
+    }
+
+    // Dice that advance when `num_movable` could, `requested` is only used by `Choose`
+    pub fn num_advancing(&self, num_movable: u32, requested: Option<u32>) -> u32 {
+        match self {
+            CaptureRule::MoveAll => num_movable,
+            CaptureRule::MoveMinimum => num_movable.min(1),
+            CaptureRule::Choose => requested
+                .unwrap_or(num_movable)
+                .clamp(1, num_movable.max(1)),
+        }
+    }
+}
+
+/// How reinforcement dice reach the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ReinforcementMode {
+    /// Spread at random over the player's territories when their turn ends
+    #[default]
+    Random,
+    /// Placed by the player with `Place` commands when their turn starts
+    Draft,
+}
+
+impl ReinforcementMode {
+    pub fn from_name(name: &str) -> Option<ReinforcementMode> {
+        match name {
+            "random" => Some(ReinforcementMode::Random),
+            "draft" => Some(ReinforcementMode::Draft),
+            _ => None,
+        }
+    }
+}
+
+/// What happens to a player who no longer holds any capital.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CapitalLoss {
+    /// The player stops taking turns, their territories stay on the board
+    #[default]
+    Eliminate,
+    /// Every territory of the player goes to whoever took the last capital
+    Transfer,
+}
+
+impl CapitalLoss {
+    pub fn from_name(name: &str) -> Option<CapitalLoss> {
+        match name {
+            "eliminate" => Some(CapitalLoss::Eliminate),
+            "transfer" => Some(CapitalLoss::Transfer),
+            _ => None,
+        }
+    }
+}
+
+/// What players cannot see of territories away from their own borders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FogOfWar {
+    /// Everyone sees the whole board
+    #[default]
+    Off,
+    /// Dice counts are hidden
+    Dice,
+    /// Dice counts, owners and capitals are hidden
+    Ownership,
+}
+
+impl FogOfWar {
+    pub fn from_name(name: &str) -> Option<FogOfWar> {
+        match name {
+            "off" => Some(FogOfWar::Off),
+            "dice" => Some(FogOfWar::Dice),
+            "ownership" => Some(FogOfWar::Ownership),
+            _ => None,
+        }
+    }
+}
+
+/// Each player has a capital territory which defends with extra dice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CapitalRules {
+    /// Dice added to a capital's roll when it is attacked
+    pub defense_bonus: u32,
+    pub on_loss: CapitalLoss,
+    /// Holding every capital wins the game, owning every territory is not needed
+    pub capital_victory: bool,
+}
+
+impl Default for CapitalRules {
+    fn default() -> CapitalRules {
+        CapitalRules {
+            defense_bonus: 1,
+            on_loss: CapitalLoss::Eliminate,
+            capital_victory: false,
+        }
+    }
+}
+
+/// Longest pact a game can allow, in rounds
+pub const MAX_PACT_ROUNDS: u32 = 1000;
+
+/// Players can agree to non-aggression pacts for a number of rounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiplomacyRules {
+    /// Longest pact that can be proposed, in rounds
+    pub max_turns: u32,
+    /// Whether a pact can be broken before it expires
+    pub allow_breaking: bool,
+}
+
+impl Default for DiplomacyRules {
+    fn default() -> DiplomacyRules {
+        DiplomacyRules {
+            max_turns: 5,
+            allow_breaking: true,
+        }
+    }
+}
+
+/// Ways to win besides owning every territory. Each one that is set can end the game.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VictoryConditions {
+    /// Once this many rounds have been played the leader wins, by territories then dice.
+    /// A round is one turn of every player.
+    pub turn_limit: Option<u32>,
+    /// Owning at least this percentage of the territories wins
+    pub territory_percent: Option<u32>,
+    /// Reaching this score wins, a territory is worth one point and
+    /// continents are worth their bonus
+    pub score_limit: Option<u32>,
+}
+
+impl VictoryConditions {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.turn_limit == Some(0) {
+            return Err("The turn limit must be at least one round".to_owned());
+        }
+        if self
+            .territory_percent
+            .is_some_and(|percent| percent == 0 || percent > 100)
+        {
+            return Err("The territory percentage must be between 1 and 100".to_owned());
+        }
+        if self.score_limit == Some(0) {
+            return Err("The score limit must be at least 1".to_owned());
+        }
+
+        Ok(())
+    }
+}
+
+/// Rules of a game that are independent of the map. Sent to clients with the board.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rules {
+    pub combat: CombatMode,
+    pub dice: DiceRules,
+    pub capture: CaptureRule,
+    pub reinforcements: ReinforcementMode,
+    /// No capitals when None
+    pub capitals: Option<CapitalRules>,
+    pub victory: VictoryConditions,
+    /// Reinforcements count the largest region held by the whole team
+    pub team_regions: bool,
+    /// No fortify phase when None
+    pub fortify: Option<FortifyRules>,
+    /// No pacts when None
+    pub diplomacy: Option<DiplomacyRules>,
+    /// Applies to seated players, spectators always see the whole board
+    pub fog: FogOfWar,
+}
+
+impl Rules {
+    pub fn validate(&self) -> Result<(), String> {
+        self.dice.validate()?;
+        self.victory.validate()?;
+
+        if self.fortify.is_some_and(|fortify| fortify.max_moves == 0) {
+            return Err("The fortify phase needs at least one move per turn".to_owned());
+        }
+        if self
+            .diplomacy
+            .is_some_and(|diplomacy| diplomacy.max_turns == 0)
+        {
+            return Err("Pacts need to last at least one round".to_owned());
+        }
+        if self
+            .diplomacy
+            .is_some_and(|diplomacy| diplomacy.max_turns > MAX_PACT_ROUNDS)
+        {
+            return Err(format!("Pacts can last at most {} rounds", MAX_PACT_ROUNDS));
+        }
+
+        Ok(())
+    }
+
+    pub fn combat_rules(&self) -> Box<dyn CombatRules> {
+        self.combat.with_dice(self.dice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dice_advancing_after_a_capture() {
+        assert_eq!(4, CaptureRule::MoveAll.num_advancing(4, Some(2)));
+        assert_eq!(1, CaptureRule::MoveMinimum.num_advancing(4, None));
+        assert_eq!(2, CaptureRule::Choose.num_advancing(4, Some(2)));
+        assert_eq!(4, CaptureRule::Choose.num_advancing(4, None));
+        // Classic combat can lose dice on the way, so a chosen count is capped by the survivors
+        assert_eq!(3, CaptureRule::Choose.num_advancing(3, Some(5)));
+    }
+
+    #[test]
+    fn pact_length_is_capped() {
+        let mut rules: Rules = Rules {
+            diplomacy: Some(DiplomacyRules {
+                max_turns: MAX_PACT_ROUNDS,
+                allow_breaking: true,
+            }),
+            ..Default::default()
+        };
+        assert!(rules.validate().is_ok());
+
+        rules.diplomacy = Some(DiplomacyRules {
+            max_turns: u32::MAX,
+            allow_breaking: true,
+        });
+        assert!(rules.validate().is_err());
+    }
+}
@@ -0,0 +1,644 @@
+use crate::gameboard::{Gameboard, Phase};
+use crate::protocol::{ClientMessage, ServerMessage};
+use crate::rules::{CaptureRule, FogOfWar};
+use crate::strategy::{self, Strategy};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+const MAX_BOT_TURNS: u32 = 10_000;
+
+/// Game state for one server, independent of the network transport. `C`
+/// identifies a connected client (an endpoint, a socket id, ...).
+pub struct GameServer<C> {
+    gameboard: Gameboard,
+    num_humans: u32,
+    bots: HashMap<u32, Box<dyn Strategy>>,
+    seats: HashMap<C, u32>,
+    clients: Vec<C>,
+}
+
+impl<C: Copy + Eq + Hash> GameServer<C> {
+    // Seats `0..num_humans` are taken by connecting clients, every seat in `bots` plays itself
+    pub fn new(
+        gameboard: Gameboard,
+        num_humans: u32,
+        bots: HashMap<u32, Box<dyn Strategy>>,
+    ) -> GameServer<C> {
+        GameServer {
+            gameboard,
+            num_humans,
+            bots,
+            seats: HashMap::new(),
+            clients: Vec::new(),
+        }
+    }
+
+    pub fn gameboard(&self) -> &Gameboard {
+        &self.gameboard
+    }
+
+    pub fn seat_of(&self, client: C) -> Option<u32> {
+        self.seats.get(&client).copied()
+    }
+
+    pub fn handle_message(&mut self, client: C, message: ClientMessage) -> Vec<(C, ServerMessage)> {
+        let mut outgoing: Vec<(C, ServerMessage)> = Vec::new();
+        let num_events: usize = self.gameboard.events.len();
+
+        match message {
+            ClientMessage::Connect => {
+                println!("Got a Connect message");
+                if !self.clients.contains(&client) {
+                    self.clients.push(client);
+                }
+
+                if !self.seats.contains_key(&client) {
+                    let free_seat: Option<u32> = (0..self.num_humans)
+                        .find(|cur_seat| !self.seats.values().any(|taken| taken == cur_seat));
+                    match free_seat {
+                        Some(seat) => {
+                            println!("Client seated as player {}", seat);
+                            self.seats.insert(client, seat);
+                            outgoing.push((client, ServerMessage::Seat { player_id: seat }));
+                        }
+                        None => println!("No free seats, client is spectating"),
+                    }
+                }
+
+                self.play_bot_turns();
+                self.broadcast_gameboard(&mut outgoing);
+            }
+            ClientMessage::Attack { from, to, count } => {
+                println!("Got an Attack message");
+                if !self.is_players_turn(client) {
+                    println!("Cannot attack: It is not this client's turn");
+                } else if !self
+                    .gameboard
+                    .is_owned_by(from, self.gameboard.current_player_id)
+                {
+                    println!("Cannot attack: Attacking territory is owned by another player");
+                } else if count.is_some() && self.gameboard.rules.capture != CaptureRule::Choose {
+                    println!("Cannot attack: This game does not let attackers choose a count");
+                } else if count.is_some_and(|count| {
+                    count == 0 || count >= self.gameboard.territory_map[&from].num_dice
+                }) {
+                    println!("Cannot attack: One die has to stay behind");
+                } else if self.gameboard.can_attack(from, to) {
+                    self.gameboard.attack_and_advance(from, to, count);
+                    self.broadcast_gameboard(&mut outgoing);
+                } else {
+                    // TODO: reply with error
+                }
+            }
+            ClientMessage::EndTurn => {
+                println!("Got an EndTurn message");
+                if !self.is_players_turn(client) {
+                    println!("Cannot end turn: It is not this client's turn");
+                } else if self.gameboard.phase == Phase::Reinforce {
+                    println!("Cannot end turn: Reinforcements have to be placed first");
+                } else {
+                    self.gameboard.end_turn();
+                    self.play_bot_turns();
+                    self.broadcast_gameboard(&mut outgoing);
+                    self.announce_reinforcements(&mut outgoing);
+                }
+            }
+            ClientMessage::Team { team } => {
+                println!("Got a Team message");
+                match self.seat_of(client) {
+                    Some(seat) => {
+                        if self.gameboard.can_join_team(seat, team) {
+                            self.gameboard.join_team(seat, team);
+                            self.broadcast_gameboard(&mut outgoing);
+                        }
+                    }
+                    None => println!("Cannot join team: Spectators have no seat"),
+                }
+            }
+            ClientMessage::Propose { partner, turns } => {
+                println!("Got a Propose message");
+                match self.seat_of(client) {
+                    Some(seat) if self.gameboard.can_propose_pact(seat, partner, turns) => {
+                        self.gameboard.propose_pact(seat, partner, turns);
+                        self.broadcast_gameboard(&mut outgoing);
+                    }
+                    Some(_) => {}
+                    None => println!("Cannot propose pact: Spectators have no seat"),
+                }
+            }
+            ClientMessage::Accept { proposer } => {
+                println!("Got an Accept message");
+                match self.seat_of(client) {
+                    Some(seat) if self.gameboard.can_accept_pact(seat, proposer) => {
+                        self.gameboard.accept_pact(seat, proposer);
+                        self.broadcast_gameboard(&mut outgoing);
+                    }
+                    Some(_) => {}
+                    None => println!("Cannot accept pact: Spectators have no seat"),
+                }
+            }
+            ClientMessage::Break { partner } => {
+                println!("Got a Break message");
+                match self.seat_of(client) {
+                    Some(seat) if self.gameboard.can_break_pact(seat, partner) => {
+                        self.gameboard.break_pact(seat, partner);
+                        self.broadcast_gameboard(&mut outgoing);
+                    }
+                    Some(_) => {}
+                    None => println!("Cannot break pact: Spectators have no seat"),
+                }
+            }
+            ClientMessage::Capital { territory } => {
+                println!("Got a Capital message");
+                if !self.is_players_turn(client) {
+                    println!("Cannot designate capital: It is not this client's turn");
+                } else if self.gameboard.can_designate_capital(territory) {
+                    self.gameboard.designate_capital(territory);
+                    self.broadcast_gameboard(&mut outgoing);
+                }
+            }
+            ClientMessage::Place { territory, count } => {
+                println!("Got a Place message");
+                if !self.is_players_turn(client) {
+                    println!("Cannot place: It is not this client's turn");
+                } else if self.gameboard.can_place(territory, count) {
+                    self.gameboard.place_dice(territory, count);
+                    self.broadcast_gameboard(&mut outgoing);
+                }
+            }
+            ClientMessage::Move { from, to, count } => {
+                println!("Got a Move message");
+                if !self.is_players_turn(client) {
+                    println!("Cannot move: It is not this client's turn");
+                } else if self.gameboard.can_move(from, to, count) {
+                    self.gameboard.move_dice(from, to, count);
+                    self.broadcast_gameboard(&mut outgoing);
+                }
+            }
+            ClientMessage::Odds { from, to } => {
+                println!("Got an Odds message");
+                match (
+                    self.gameboard.territory_map.get(&from),
+                    self.gameboard.territory_map.get(&to),
+                ) {
+                    (Some(_), Some(_))
+                        if self.seat_of(client).is_some_and(|seat| {
+                            !self.gameboard.can_see(seat, from) || !self.gameboard.can_see(seat, to)
+                        }) =>
+                    {
+                        println!("Odds requested for territories hidden by the fog of war")
+                    }
+                    (Some(attacker), Some(defender)) => {
+                        let probability: f64 =
+                            self.gameboard.rules.combat_rules().success_probability(
+                                attacker.num_dice,
+                                defender.num_dice + self.gameboard.defense_bonus(to),
+                            );
+                        outgoing.push((
+                            client,
+                            ServerMessage::Odds {
+                                from,
+                                to,
+                                probability,
+                            },
+                        ));
+                    }
+                    _ => println!("Odds requested for unknown territories"),
+                }
+            }
+        }
+
+        if let Some(winner) = self.gameboard.winner() {
+            if self.gameboard.teams.is_empty() {
+                println!("Player {} has won", winner);
+            } else {
+                println!("Team {} has won", self.gameboard.team_of(winner));
+            }
+        }
+
+        self.announce_events(num_events, &mut outgoing);
+
+        outgoing
+    }
+
+    pub fn disconnect(&mut self, client: C) {
+        self.seats.remove(&client);
+        self.clients.retain(|cur_client| *cur_client != client);
+    }
+
+    fn is_players_turn(&self, client: C) -> bool {
+        self.seat_of(client) == Some(self.gameboard.current_player_id)
+            && self.gameboard.winner().is_none()
+    }
+
+    fn play_bot_turns(&mut self) {
+        // Bots that refuse to attack each other would otherwise stall the server forever
+        for _cur_turn in 0..MAX_BOT_TURNS {
+            if self.gameboard.winner().is_some() {
+                break;
+            }
+
+            let bot: &mut Box<dyn Strategy> =
+                match self.bots.get_mut(&self.gameboard.current_player_id) {
+                    Some(bot) => bot,
+                    None => break,
+                };
+
+            println!(
+                "Playing turn for player {} ({} bot)",
+                self.gameboard.current_player_id,
+                bot.name()
+            );
+            strategy::play_turn(bot.as_mut(), &mut self.gameboard);
+        }
+    }
+
+    fn announce_reinforcements(&self, outgoing: &mut Vec<(C, ServerMessage)>) {
+        if self.gameboard.phase != Phase::Reinforce {
+            return;
+        }
+
+        for cur_client in &self.clients {
+            // Under fog the count would reveal the size of the player's largest region
+            let is_other_seat: bool = self
+                .seat_of(*cur_client)
+                .is_some_and(|seat| seat != self.gameboard.current_player_id);
+            if self.gameboard.rules.fog != FogOfWar::Off && is_other_seat {
+                continue;
+            }
+
+            outgoing.push((
+                *cur_client,
+                ServerMessage::Reinforcements {
+                    player_id: self.gameboard.current_player_id,
+                    count: self.gameboard.pending_reinforcements,
+                },
+            ));
+        }
+    }
+
+    // Everything logged since the log had `num_events` entries
+    fn announce_events(&self, num_events: usize, outgoing: &mut Vec<(C, ServerMessage)>) {
+        for cur_event in &self.gameboard.events[num_events..] {
+            for cur_client in &self.clients {
+                outgoing.push((*cur_client, ServerMessage::Event(*cur_event)));
+            }
+        }
+    }
+
+    // Seated clients get their own view of the board, spectators the whole board
+    fn broadcast_gameboard(&self, outgoing: &mut Vec<(C, ServerMessage)>) {
+        for cur_client in &self.clients {
+            let gameboard: Gameboard = match self.seat_of(*cur_client) {
+                Some(seat) => self.gameboard.view_for(seat),
+                None => self.gameboard.clone(),
+            };
+            outgoing.push((*cur_client, ServerMessage::Gameboard(Box::new(gameboard))));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diplomacy::EventKind;
+    use crate::gameboard::Territory;
+    use crate::rules::{DiplomacyRules, FogOfWar, FortifyRules, ReinforcementMode};
+    use crate::strategy::GreedyStrategy;
+
+    #[test]
+    fn clients_take_free_seats_then_spectate() {
+        let mut server: GameServer<u32> =
+            GameServer::new(Gameboard::new(2, 3, 6), 2, HashMap::new());
+
+        server.handle_message(10, ClientMessage::Connect);
+        let outgoing = server.handle_message(11, ClientMessage::Connect);
+        assert!(matches!(
+            outgoing[0],
+            (11, ServerMessage::Seat { player_id: 1 })
+        ));
+        let outgoing = server.handle_message(12, ClientMessage::Connect);
+
+        assert_eq!(Some(0), server.seat_of(10));
+        assert_eq!(Some(1), server.seat_of(11));
+        assert_eq!(None, server.seat_of(12));
+        assert_eq!(3, outgoing.len());
+
+        server.disconnect(10);
+        let outgoing = server.handle_message(12, ClientMessage::Connect);
+        assert_eq!(Some(0), server.seat_of(12));
+        assert!(matches!(
+            outgoing[0],
+            (12, ServerMessage::Seat { player_id: 0 })
+        ));
+    }
+
+    #[test]
+    fn only_current_player_can_end_turn() {
+        let mut server: GameServer<u32> =
+            GameServer::new(Gameboard::new(2, 3, 6), 2, HashMap::new());
+        server.handle_message(10, ClientMessage::Connect);
+        server.handle_message(11, ClientMessage::Connect);
+
+        assert!(server.handle_message(11, ClientMessage::EndTurn).is_empty());
+        assert_eq!(0, server.gameboard().current_player_id);
+
+        assert_eq!(2, server.handle_message(10, ClientMessage::EndTurn).len());
+        assert_eq!(1, server.gameboard().current_player_id);
+    }
+
+    #[test]
+    fn bots_play_until_a_human_turn() {
+        let mut bots: HashMap<u32, Box<dyn Strategy>> = HashMap::new();
+        bots.insert(1, Box::new(GreedyStrategy));
+        let mut server: GameServer<u32> = GameServer::new(Gameboard::new(2, 3, 6), 1, bots);
+        server.handle_message(10, ClientMessage::Connect);
+
+        server.handle_message(10, ClientMessage::EndTurn);
+
+        let gameboard: &Gameboard = server.gameboard();
+        assert!(gameboard.winner().is_some() || gameboard.current_player_id == 0);
+    }
+
+    #[test]
+    fn moves_need_a_fortify_phase_and_the_turn() {
+        let territory_map: HashMap<u32, Territory> = [(0, 3, 0), (1, 1, 0), (2, 2, 1)]
+            .iter()
+            .map(|(id, num_dice, owner_id)| {
+                let neighbors: Vec<u32> = if *id == 1 { vec![0, 2] } else { vec![1] };
+                let territory = Territory {
+                    id: *id,
+                    num_dice: *num_dice,
+                    owner_id: *owner_id,
+                    neighbors,
+                    ..Default::default()
+                };
+                (*id, territory)
+            })
+            .collect();
+        let mut gameboard: Gameboard = Gameboard::from_territory_map(territory_map, 2);
+        let message = ClientMessage::Move {
+            from: 0,
+            to: 1,
+            count: 2,
+        };
+
+        let mut server: GameServer<u32> = GameServer::new(gameboard.clone(), 2, HashMap::new());
+        server.handle_message(10, ClientMessage::Connect);
+        assert!(server.handle_message(10, message).is_empty());
+
+        gameboard.rules.fortify = Some(FortifyRules::default());
+        let mut server: GameServer<u32> = GameServer::new(gameboard, 2, HashMap::new());
+        server.handle_message(10, ClientMessage::Connect);
+        server.handle_message(11, ClientMessage::Connect);
+
+        assert!(server.handle_message(11, message).is_empty());
+        assert_eq!(2, server.handle_message(10, message).len());
+        assert_eq!(3, server.gameboard().territory_map[&1].num_dice);
+        assert_eq!(Phase::Fortify, server.gameboard().phase);
+    }
+
+    #[test]
+    fn attack_counts_need_the_choose_rule() {
+        let mut gameboard: Gameboard = Gameboard::new_seeded(2, 3, 9, 4);
+        let (from, to): (u32, u32) = gameboard.legal_attacks(0)[0];
+        let message = ClientMessage::Attack {
+            from,
+            to,
+            count: Some(1),
+        };
+
+        let mut server: GameServer<u32> = GameServer::new(gameboard.clone(), 2, HashMap::new());
+        server.handle_message(10, ClientMessage::Connect);
+        assert!(server.handle_message(10, message).is_empty());
+
+        gameboard.rules.capture = CaptureRule::Choose;
+        let mut server: GameServer<u32> = GameServer::new(gameboard, 2, HashMap::new());
+        server.handle_message(10, ClientMessage::Connect);
+        assert_eq!(1, server.handle_message(10, message).len());
+    }
+
+    #[test]
+    fn seated_clients_join_teams_in_the_lobby() {
+        let gameboard: Gameboard = Gameboard::new_seeded(3, 3, 6, 5);
+        let mut server: GameServer<u32> = GameServer::new(gameboard, 2, HashMap::new());
+        server.handle_message(10, ClientMessage::Connect);
+        server.handle_message(11, ClientMessage::Connect);
+        server.handle_message(12, ClientMessage::Connect);
+
+        assert_eq!(
+            3,
+            server
+                .handle_message(11, ClientMessage::Team { team: 0 })
+                .len()
+        );
+        assert_eq!(vec![0, 0, 2], server.gameboard().teams);
+
+        // Spectators have no seat to move, and teams are numbered like seats
+        assert!(server
+            .handle_message(12, ClientMessage::Team { team: 0 })
+            .is_empty());
+        assert!(server
+            .handle_message(10, ClientMessage::Team { team: 3 })
+            .is_empty());
+    }
+
+    #[test]
+    fn teams_cannot_leave_a_single_team() {
+        let gameboard: Gameboard = Gameboard::new_seeded(2, 3, 6, 5);
+        let mut server: GameServer<u32> = GameServer::new(gameboard, 2, HashMap::new());
+        server.handle_message(10, ClientMessage::Connect);
+        server.handle_message(11, ClientMessage::Connect);
+
+        assert!(server
+            .handle_message(11, ClientMessage::Team { team: 0 })
+            .is_empty());
+        assert_eq!(None, server.gameboard().winner());
+        assert!(!server.handle_message(10, ClientMessage::EndTurn).is_empty());
+    }
+
+    #[test]
+    fn pacts_are_announced_to_everyone() {
+        let mut gameboard: Gameboard = Gameboard::new_seeded(2, 3, 6, 5);
+        gameboard.rules.diplomacy = Some(DiplomacyRules::default());
+        let mut server: GameServer<u32> = GameServer::new(gameboard, 2, HashMap::new());
+        server.handle_message(10, ClientMessage::Connect);
+        server.handle_message(11, ClientMessage::Connect);
+        server.handle_message(12, ClientMessage::Connect);
+
+        let announced = |outgoing: Vec<(u32, ServerMessage)>| -> Vec<(u32, EventKind)> {
+            outgoing
+                .into_iter()
+                .filter_map(|(client, message)| match message {
+                    ServerMessage::Event(event) => Some((client, event.kind)),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        // Player 0 cannot accept their own offer
+        server.handle_message(
+            10,
+            ClientMessage::Propose {
+                partner: 1,
+                turns: 2,
+            },
+        );
+        assert!(server
+            .handle_message(10, ClientMessage::Accept { proposer: 0 })
+            .is_empty());
+        assert!(server
+            .handle_message(12, ClientMessage::Accept { proposer: 0 })
+            .is_empty());
+
+        let outgoing = server.handle_message(11, ClientMessage::Accept { proposer: 0 });
+        assert!(server.gameboard().has_pact(0, 1));
+        assert_eq!(3, announced(outgoing).len());
+
+        let broken = EventKind::PactBroken {
+            breaker: 1,
+            partner: 0,
+        };
+        let outgoing = server.handle_message(11, ClientMessage::Break { partner: 0 });
+        assert_eq!(
+            vec![(10, broken), (11, broken), (12, broken)],
+            announced(outgoing)
+        );
+        assert!(!server.gameboard().has_pact(0, 1));
+    }
+
+    #[test]
+    fn fog_of_war_views_depend_on_the_seat() {
+        let mut gameboard: Gameboard = Gameboard::new_seeded(3, 8, 16, 3);
+        gameboard.rules.fog = FogOfWar::Dice;
+        let mut server: GameServer<u32> = GameServer::new(gameboard, 2, HashMap::new());
+        server.handle_message(10, ClientMessage::Connect);
+        server.handle_message(11, ClientMessage::Connect);
+        let outgoing = server.handle_message(12, ClientMessage::Connect);
+
+        for (client, message) in outgoing {
+            let view: Gameboard = match message {
+                ServerMessage::Gameboard(view) => *view,
+                _ => panic!("Expected a gameboard"),
+            };
+            for cur_terr in view.territory_map.values() {
+                let visible: bool = match server.seat_of(client) {
+                    Some(seat) => server
+                        .gameboard()
+                        .visible_territories(seat)
+                        .contains(&cur_terr.id),
+                    None => true,
+                };
+                assert_eq!(!visible, cur_terr.hidden);
+            }
+        }
+
+        // Spectators can ask about anything
+        let hidden: Option<u32> = server
+            .gameboard()
+            .territory_map
+            .keys()
+            .copied()
+            .find(|terr_id| !server.gameboard().can_see(0, *terr_id));
+        let to: u32 = hidden.expect("the seeded board has territories player 0 cannot see");
+        assert!(server
+            .handle_message(10, ClientMessage::Odds { from: to, to })
+            .is_empty());
+        assert_eq!(
+            1,
+            server
+                .handle_message(12, ClientMessage::Odds { from: to, to })
+                .len()
+        );
+    }
+
+    #[test]
+    fn draft_turns_are_announced_and_placed() {
+        let mut gameboard: Gameboard = Gameboard::new_seeded(2, 3, 6, 5);
+        gameboard.rules.reinforcements = ReinforcementMode::Draft;
+        let mut server: GameServer<u32> = GameServer::new(gameboard, 2, HashMap::new());
+        server.handle_message(10, ClientMessage::Connect);
+        server.handle_message(11, ClientMessage::Connect);
+
+        let outgoing = server.handle_message(10, ClientMessage::EndTurn);
+        let count: u32 = server.gameboard().pending_reinforcements;
+        assert!(count > 0);
+        let announced: Vec<(u32, u32, u32)> = outgoing
+            .iter()
+            .filter_map(|(client, message)| match message {
+                ServerMessage::Reinforcements { player_id, count } => {
+                    Some((*client, *player_id, *count))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(vec![(10, 1, count), (11, 1, count)], announced);
+
+        // Player 1 cannot skip placing
+        assert!(server.handle_message(11, ClientMessage::EndTurn).is_empty());
+
+        let territory: u32 = server
+            .gameboard()
+            .territory_map
+            .values()
+            .filter(|terr| terr.owner_id == 1)
+            .min_by_key(|terr| terr.num_dice)
+            .unwrap()
+            .id;
+        assert_eq!(
+            2,
+            server
+                .handle_message(
+                    11,
+                    ClientMessage::Place {
+                        territory,
+                        count: 1
+                    }
+                )
+                .len()
+        );
+        assert_eq!(count - 1, server.gameboard().pending_reinforcements);
+    }
+
+    #[test]
+    fn fog_of_war_hides_other_seats_reinforcements() {
+        let mut gameboard: Gameboard = Gameboard::new_seeded(2, 3, 6, 5);
+        gameboard.rules.reinforcements = ReinforcementMode::Draft;
+        gameboard.rules.fog = FogOfWar::Dice;
+        let mut server: GameServer<u32> = GameServer::new(gameboard, 2, HashMap::new());
+        server.handle_message(10, ClientMessage::Connect);
+        server.handle_message(11, ClientMessage::Connect);
+        server.handle_message(12, ClientMessage::Connect);
+
+        let outgoing = server.handle_message(10, ClientMessage::EndTurn);
+        let count: u32 = server.gameboard().pending_reinforcements;
+        let announced: Vec<u32> = outgoing
+            .iter()
+            .filter_map(|(client, message)| match message {
+                ServerMessage::Reinforcements { .. } => Some(*client),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(vec![11, 12], announced);
+
+        for (client, message) in outgoing {
+            if let ServerMessage::Gameboard(view) = message {
+                let expected: u32 = if client == 10 { 0 } else { count };
+                assert_eq!(expected, view.pending_reinforcements);
+            }
+        }
+    }
+
+    #[test]
+    fn odds_reply_goes_to_sender_only() {
+        let mut server: GameServer<u32> =
+            GameServer::new(Gameboard::new(2, 3, 6), 2, HashMap::new());
+        server.handle_message(10, ClientMessage::Connect);
+        server.handle_message(11, ClientMessage::Connect);
+
+        let outgoing = server.handle_message(11, ClientMessage::Odds { from: 0, to: 1 });
+
+        assert_eq!(1, outgoing.len());
+        assert_eq!(11, outgoing[0].0);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::gameboard::Gameboard;
+use crate::mcts::{MctsStrategy, SearchBudget, DEFAULT_ITERATIONS};
+use rand::prelude::SliceRandom;
+use rand::thread_rng;
+use rand::{Rng, RngCore};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Attack { from: u32, to: u32 },
+    EndTurn,
+}
+
+/// A computer-controlled player. `next_action` is called repeatedly during the
+/// bot's turn, so a turn is the sequence of attacks it returns up to `EndTurn`.
+/// Strategies draw all their randomness from `rng`, so seeded games replay exactly.
+pub trait Strategy {
+    fn name(&self) -> &str;
+
+    fn next_action(
+        &mut self,
+        gameboard: &Gameboard,
+        player_id: u32,
+        rng: &mut dyn RngCore,
+    ) -> Action;
+}
+
+pub struct RandomStrategy;
+
+impl Strategy for RandomStrategy {
+    fn name(&self) -> &str {
+        "random"
+    }
+
+    fn next_action(
+        &mut self,
+        gameboard: &Gameboard,
+        player_id: u32,
+        rng: &mut dyn RngCore,
+    ) -> Action {
+        let attacks: Vec<(u32, u32)> = gameboard.legal_attacks(player_id);
+
+        // Ending the turn is always one of the options, so random bots do not
+        // throw every die they have at the first border they find.
+        if attacks.is_empty() || rng.gen_range(0..=attacks.len()) == attacks.len() {
+            return Action::EndTurn;
+        }
+
+        let (from, to) = *attacks.choose(rng).unwrap();
+        Action::Attack { from, to }
+    }
+}
+
+pub struct GreedyStrategy;
+
+impl Strategy for GreedyStrategy {
+    fn name(&self) -> &str {
+        "greedy"
+    }
+
+    fn next_action(
+        &mut self,
+        gameboard: &Gameboard,
+        player_id: u32,
+        _rng: &mut dyn RngCore,
+    ) -> Action {
+        let mut best_attack: Option<(u32, u32)> = None;
+        let mut best_margin: u32 = 0;
+
+        for (from, to) in gameboard.legal_attacks(player_id) {
+            let attacker_dice: u32 = gameboard.territory_map[&from].num_dice;
+            let defender_dice: u32 = gameboard.territory_map[&to].num_dice;
+
+            if attacker_dice > defender_dice && attacker_dice - defender_dice > best_margin {
+                best_margin = attacker_dice - defender_dice;
+                best_attack = Some((from, to));
+            }
+        }
+
+        match best_attack {
+            Some((from, to)) => Action::Attack { from, to },
+            None => Action::EndTurn,
+        }
+    }
+}
+
+// Strategies are named as `name[:parameter]`, e.g. `mcts:5000` or `mcts:250ms`
+pub fn from_name(name: &str) -> Option<Box<dyn Strategy>> {
+    let (base_name, parameter): (&str, Option<&str>) = match name.split_once(':') {
+        Some((base_name, parameter)) => (base_name, Some(parameter)),
+        None => (name, None),
+    };
+
+    match (base_name, parameter) {
+        ("random", None) => Some(Box::new(RandomStrategy)),
+        ("greedy", None) => Some(Box::new(GreedyStrategy)),
+        ("mcts", None) => Some(Box::new(MctsStrategy::new(SearchBudget::Iterations(
+            DEFAULT_ITERATIONS,
+        )))),
+        ("mcts", Some(budget)) => Some(Box::new(MctsStrategy::new(SearchBudget::parse(budget)?))),
+        _ => None,
+    }
+}
+
+pub fn play_turn(strategy: &mut dyn Strategy, gameboard: &mut Gameboard) {
+    play_turn_with_rng(strategy, gameboard, &mut thread_rng());
+}
+
+pub fn play_turn_with_rng<R: Rng + ?Sized>(
+    strategy: &mut dyn Strategy,
+    gameboard: &mut Gameboard,
+    rng: &mut R,
+) {
+    let mut rng: &mut R = rng;
+    let player_id: u32 = gameboard.current_player_id;
+    gameboard.place_at_random_with_rng(rng);
+
+    while gameboard.winner().is_none() {
+        match strategy.next_action(gameboard, player_id, &mut rng) {
+            Action::Attack { from, to } => {
+                if !gameboard.is_owned_by(from, player_id) || !gameboard.can_attack(from, to) {
+                    println!("Bot {} tried an illegal attack, ending its turn", player_id);
+                    break;
+                }
+
+                gameboard.attack_with_rng(from, to, rng);
+            }
+            Action::EndTurn => break,
+        }
+    }
+
+    if gameboard.winner().is_none() {
+        gameboard.end_turn_with_rng(rng);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn greedy_only_attacks_weaker_neighbors() {
+        let mut gameboard: Gameboard = Gameboard::new(2, 4, 12);
+        let mut strategy = GreedyStrategy;
+
+        for _cur_step in 0..20 {
+            let player_id: u32 = gameboard.current_player_id;
+            match strategy.next_action(&gameboard, player_id, &mut thread_rng()) {
+                Action::Attack { from, to } => {
+                    assert!(
+                        gameboard.territory_map[&from].num_dice
+                            > gameboard.territory_map[&to].num_dice
+                    );
+                    gameboard.attack(from, to);
+                }
+                Action::EndTurn => gameboard.end_turn(),
+            }
+
+            if gameboard.winner().is_some() {
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn random_only_picks_legal_attacks() {
+        let gameboard: Gameboard = Gameboard::new(3, 3, 9);
+        let mut strategy = RandomStrategy;
+
+        for _cur_step in 0..50 {
+            if let Action::Attack { from, to } =
+                strategy.next_action(&gameboard, 0, &mut thread_rng())
+            {
+                assert!(gameboard.legal_attacks(0).contains(&(from, to)));
+            }
+        }
+    }
+
+    #[test]
+    fn from_name_parses_parameters() {
+        assert_eq!("mcts", from_name("mcts:250ms").unwrap().name());
+        assert_eq!("mcts", from_name("mcts:500").unwrap().name());
+        assert!(from_name("mcts:fast").is_none());
+        assert!(from_name("greedy:5").is_none());
+        assert!(from_name("smart").is_none());
+    }
+
+    #[test]
+    fn bots_take_turns() {
+        // Seeded so the greedy bot cannot win before the other bot gets a turn
+        let mut gameboard: Gameboard = Gameboard::new_seeded(2, 4, 10, 1);
+        let mut rng: StdRng = StdRng::seed_from_u64(1);
+        let mut bots: Vec<Box<dyn Strategy>> =
+            vec![from_name("greedy").unwrap(), from_name("random").unwrap()];
+
+        while gameboard.winner().is_none() && gameboard.turn_number < 1000 {
+            let player_id: usize = gameboard.current_player_id as usize;
+            play_turn_with_rng(bots[player_id].as_mut(), &mut gameboard, &mut rng);
+        }
+
+        assert!(gameboard.turn_number > 1);
+    }
+}
@@ -0,0 +1,442 @@
+use crate::gameboard::{self, Territory};
+use rand::prelude::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub const DEFAULT_AVERAGE_DEGREE: f64 = 3.0;
+
+// Extra edges are drawn at random, so give up after this many misses per edge
+const ATTEMPTS_PER_EDGE: usize = 50;
+
+/// Shape of the adjacency graph for `MapStyle::Graph` boards.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TopologyOptions {
+    pub min_neighbors: u32,
+    pub max_neighbors: Option<u32>,
+    pub average_degree: f64,
+    /// Exact number of bridges (edges whose removal splits the board). Bridges
+    /// are the links between continents, so this needs `num_bridges + 1` continents.
+    pub num_bridges: Option<u32>,
+    pub num_continents: u32,
+    /// Edges between two linked continents that are not bridges
+    pub continent_links: u32,
+}
+
+impl Default for TopologyOptions {
+    fn default() -> TopologyOptions {
+        TopologyOptions {
+            min_neighbors: 1,
+            max_neighbors: None,
+            average_degree: DEFAULT_AVERAGE_DEGREE,
+            num_bridges: None,
+            num_continents: 1,
+            continent_links: 2,
+        }
+    }
+}
+
+impl TopologyOptions {
+    pub fn validate(&self, num_territories: u32) -> Result<(), String> {
+        if self.num_continents == 0 || self.num_continents > num_territories {
+            return Err(format!(
+                "The number of continents must be between 1 and the number of territories ({})",
+                num_territories
+            ));
+        }
+
+        if self.continent_links == 0 {
+            return Err("Continents need at least one link between them".to_owned());
+        }
+
+        if self.average_degree.is_nan() || self.average_degree < 0.0 {
+            return Err("The average degree cannot be negative".to_owned());
+        }
+
+        let smallest_continent: u32 = num_territories / self.num_continents;
+        if self.min_neighbors > smallest_continent.saturating_sub(1) && self.min_neighbors > 1 {
+            return Err(format!(
+                "Continents of {} territories cannot give every territory {} neighbors",
+                smallest_continent, self.min_neighbors
+            ));
+        }
+
+        if let Some(max_neighbors) = self.max_neighbors {
+            if max_neighbors < 2 && num_territories > 2 {
+                return Err(
+                    "A connected board needs territories with at least 2 neighbors".to_owned(),
+                );
+            }
+            if max_neighbors < self.min_neighbors {
+                return Err("The maximum neighbors cannot be below the minimum".to_owned());
+            }
+            if self.average_degree > f64::from(max_neighbors) {
+                return Err("The average degree cannot exceed the maximum neighbors".to_owned());
+            }
+        }
+
+        if let Some(num_bridges) = self.num_bridges {
+            if num_bridges + 1 > self.num_continents {
+                return Err(format!(
+                    "{} bridges need at least {} continents",
+                    num_bridges,
+                    num_bridges + 1
+                ));
+            }
+            if self.num_continents > 1 && smallest_continent < 3 {
+                return Err(
+                    "Continents need at least 3 territories to avoid extra bridges".to_owned(),
+                );
+            }
+            if num_bridges + 1 < self.num_continents && self.continent_links < 2 {
+                return Err(
+                    "Continent links must be at least 2 when they are not bridges".to_owned(),
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Wires up `territory_map` according to `options` and returns the continents
+// (groups of territory ids) the board was built from. The result is always connected.
+pub fn connect_graph<R: Rng + ?Sized>(
+    territory_map: &mut HashMap<u32, Territory>,
+    options: &TopologyOptions,
+    rng: &mut R,
+) -> Vec<Vec<u32>> {
+    gameboard::clear_neighbors(territory_map);
+
+    let mut all_terr_ids: Vec<u32> = territory_map.keys().copied().collect();
+    all_terr_ids.sort_unstable();
+    all_terr_ids.shuffle(rng);
+
+    let num_continents: usize = options.num_continents.max(1) as usize;
+    let continents: Vec<Vec<u32>> = (0..num_continents)
+        .map(|cur_continent| {
+            all_terr_ids
+                .iter()
+                .skip(cur_continent)
+                .step_by(num_continents)
+                .copied()
+                .collect()
+        })
+        .collect();
+
+    for cur_continent in &continents {
+        connect_continent(territory_map, cur_continent, options, rng);
+    }
+
+    let num_bridges: u32 = options.num_bridges.unwrap_or(0);
+    for cur_continent in 1..continents.len() {
+        let other_continent: usize = rng.gen_range(0..cur_continent);
+        let num_links: u32 = if (cur_continent as u32) <= num_bridges {
+            1
+        } else {
+            options.continent_links
+        };
+
+        let mut links_added: u32 = 0;
+        for _cur_attempt in 0..(num_links as usize * ATTEMPTS_PER_EDGE) {
+            if links_added == num_links {
+                break;
+            }
+
+            let first: u32 = *continents[cur_continent].choose(rng).unwrap();
+            let second: u32 = *continents[other_continent].choose(rng).unwrap();
+            if can_add_edge(territory_map, first, second, options.max_neighbors) {
+                add_edge(territory_map, first, second);
+                links_added += 1;
+            }
+        }
+
+        if links_added == 0 {
+            // Connectivity wins over the neighbor cap
+            let first: u32 = *continents[cur_continent].choose(rng).unwrap();
+            let second: u32 = *continents[other_continent].choose(rng).unwrap();
+            add_edge(territory_map, first, second);
+        }
+    }
+
+    for cur_continent in &continents {
+        for cur_terr_id in cur_continent {
+            let mut candidates: Vec<u32> = cur_continent
+                .iter()
+                .copied()
+                .filter(|other_id| {
+                    can_add_edge(
+                        territory_map,
+                        *cur_terr_id,
+                        *other_id,
+                        options.max_neighbors,
+                    )
+                })
+                .collect();
+            candidates.shuffle(rng);
+
+            for cur_candidate in candidates {
+                if territory_map[cur_terr_id].neighbors.len() >= options.min_neighbors as usize {
+                    break;
+                }
+                if can_add_edge(
+                    territory_map,
+                    *cur_terr_id,
+                    cur_candidate,
+                    options.max_neighbors,
+                ) {
+                    add_edge(territory_map, *cur_terr_id, cur_candidate);
+                }
+            }
+        }
+    }
+
+    for cur_terr in territory_map.values_mut() {
+        cur_terr.neighbors.sort_unstable();
+    }
+
+    continents
+}
+
+fn connect_continent<R: Rng + ?Sized>(
+    territory_map: &mut HashMap<u32, Territory>,
+    continent: &[u32],
+    options: &TopologyOptions,
+    rng: &mut R,
+) {
+    let size: usize = continent.len();
+
+    if options.num_bridges.is_some() && size >= 3 {
+        // A ring has no bridges, so the only bridges are the continent links
+        for cur_index in 0..size {
+            add_edge(
+                territory_map,
+                continent[cur_index],
+                continent[(cur_index + 1) % size],
+            );
+        }
+    } else {
+        for cur_index in 1..size {
+            let open_parents: Vec<u32> = continent[..cur_index]
+                .iter()
+                .copied()
+                .filter(|parent| below_max(territory_map, *parent, options.max_neighbors))
+                .collect();
+            let parent: u32 = *open_parents
+                .choose(rng)
+                .unwrap_or(&continent[cur_index - 1]);
+            add_edge(territory_map, continent[cur_index], parent);
+        }
+    }
+
+    let max_edges: usize = size * size.saturating_sub(1) / 2;
+    let target_edges: usize =
+        ((options.average_degree * size as f64 / 2.0).ceil() as usize).min(max_edges);
+    let mut num_edges: usize = continent
+        .iter()
+        .map(|terr_id| territory_map[terr_id].neighbors.len())
+        .sum::<usize>()
+        / 2;
+
+    let mut attempts_left: usize = target_edges.saturating_sub(num_edges) * ATTEMPTS_PER_EDGE;
+    while num_edges < target_edges && attempts_left > 0 {
+        attempts_left -= 1;
+
+        let first: u32 = *continent.choose(rng).unwrap();
+        let second: u32 = *continent.choose(rng).unwrap();
+        if can_add_edge(territory_map, first, second, options.max_neighbors) {
+            add_edge(territory_map, first, second);
+            num_edges += 1;
+        }
+    }
+}
+
+fn below_max(
+    territory_map: &HashMap<u32, Territory>,
+    terr_id: u32,
+    max_neighbors: Option<u32>,
+) -> bool {
+    match max_neighbors {
+        Some(max_neighbors) => territory_map[&terr_id].neighbors.len() < max_neighbors as usize,
+        None => true,
+    }
+}
+
+fn can_add_edge(
+    territory_map: &HashMap<u32, Territory>,
+    first: u32,
+    second: u32,
+    max_neighbors: Option<u32>,
+) -> bool {
+    first != second
+        && !territory_map[&first].neighbors.contains(&second)
+        && below_max(territory_map, first, max_neighbors)
+        && below_max(territory_map, second, max_neighbors)
+}
+
+fn add_edge(territory_map: &mut HashMap<u32, Territory>, first: u32, second: u32) {
+    territory_map
+        .get_mut(&first)
+        .unwrap()
+        .neighbors
+        .push(second);
+    territory_map
+        .get_mut(&second)
+        .unwrap()
+        .neighbors
+        .push(first);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn empty_territories(num_territories: u32) -> HashMap<u32, Territory> {
+        (0..num_territories)
+            .map(|cur_id| {
+                (
+                    cur_id,
+                    Territory {
+                        id: cur_id,
+                        num_dice: 1,
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect()
+    }
+
+    fn is_connected_without(
+        territory_map: &HashMap<u32, Territory>,
+        removed: Option<(u32, u32)>,
+    ) -> bool {
+        let mut visited: Vec<u32> = vec![0];
+        let mut stack: Vec<u32> = vec![0];
+
+        while let Some(cur_id) = stack.pop() {
+            for cur_neighbor in &territory_map[&cur_id].neighbors {
+                let is_removed: bool = removed == Some((cur_id, *cur_neighbor))
+                    || removed == Some((*cur_neighbor, cur_id));
+                if !is_removed && !visited.contains(cur_neighbor) {
+                    visited.push(*cur_neighbor);
+                    stack.push(*cur_neighbor);
+                }
+            }
+        }
+
+        visited.len() == territory_map.len()
+    }
+
+    fn count_bridges(territory_map: &HashMap<u32, Territory>) -> u32 {
+        let mut num_bridges: u32 = 0;
+
+        for cur_terr in territory_map.values() {
+            for cur_neighbor in &cur_terr.neighbors {
+                if cur_terr.id < *cur_neighbor
+                    && !is_connected_without(territory_map, Some((cur_terr.id, *cur_neighbor)))
+                {
+                    num_bridges += 1;
+                }
+            }
+        }
+
+        num_bridges
+    }
+
+    #[test]
+    fn neighbor_limits_are_respected() {
+        let mut territory_map: HashMap<u32, Territory> = empty_territories(60);
+        let options = TopologyOptions {
+            min_neighbors: 2,
+            max_neighbors: Some(4),
+            average_degree: 3.5,
+            ..TopologyOptions::default()
+        };
+
+        connect_graph(&mut territory_map, &options, &mut StdRng::seed_from_u64(1));
+
+        assert!(is_connected_without(&territory_map, None));
+        for cur_terr in territory_map.values() {
+            assert!(cur_terr.neighbors.len() >= 2);
+            assert!(cur_terr.neighbors.len() <= 4);
+        }
+    }
+
+    #[test]
+    fn exact_number_of_bridges() {
+        let mut territory_map: HashMap<u32, Territory> = empty_territories(40);
+        let options = TopologyOptions {
+            num_bridges: Some(3),
+            num_continents: 5,
+            ..TopologyOptions::default()
+        };
+
+        let continents: Vec<Vec<u32>> =
+            connect_graph(&mut territory_map, &options, &mut StdRng::seed_from_u64(2));
+
+        assert_eq!(5, continents.len());
+        assert!(is_connected_without(&territory_map, None));
+        assert_eq!(3, count_bridges(&territory_map));
+    }
+
+    #[test]
+    fn continents_are_sparsely_linked() {
+        let mut territory_map: HashMap<u32, Territory> = empty_territories(30);
+        let options = TopologyOptions {
+            num_continents: 3,
+            continent_links: 2,
+            average_degree: 4.0,
+            ..TopologyOptions::default()
+        };
+
+        let continents: Vec<Vec<u32>> =
+            connect_graph(&mut territory_map, &options, &mut StdRng::seed_from_u64(3));
+
+        let mut continent_of: HashMap<u32, usize> = HashMap::new();
+        for (cur_index, cur_continent) in continents.iter().enumerate() {
+            for cur_terr_id in cur_continent {
+                continent_of.insert(*cur_terr_id, cur_index);
+            }
+        }
+
+        let mut cross_edges: usize = 0;
+        for cur_terr in territory_map.values() {
+            for cur_neighbor in &cur_terr.neighbors {
+                if continent_of[&cur_terr.id] != continent_of[cur_neighbor] {
+                    cross_edges += 1;
+                }
+            }
+        }
+
+        // Two continent links of two edges each, counted from both ends
+        assert_eq!(8, cross_edges);
+        assert!(is_connected_without(&territory_map, None));
+    }
+
+    #[test]
+    fn validate_rejects_impossible_options() {
+        let too_many_bridges = TopologyOptions {
+            num_bridges: Some(2),
+            num_continents: 2,
+            ..TopologyOptions::default()
+        };
+        assert!(too_many_bridges.validate(20).is_err());
+
+        let cap_too_low = TopologyOptions {
+            max_neighbors: Some(1),
+            ..TopologyOptions::default()
+        };
+        assert!(cap_too_low.validate(20).is_err());
+
+        let too_many_continents = TopologyOptions {
+            num_continents: 21,
+            ..TopologyOptions::default()
+        };
+        assert!(too_many_continents.validate(20).is_err());
+
+        assert!(TopologyOptions::default().validate(20).is_ok());
+    }
+}
@@ -0,0 +1,315 @@
+use crate::gameboard::{BoardConfig, Gameboard, DEFAULT_MAX_DICE};
+use crate::strategy::{self, Strategy};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+const CONFIDENCE_Z: f64 = 1.96;
+
+#[derive(Debug, Clone)]
+pub struct TournamentConfig {
+    pub strategies: Vec<String>,
+    pub num_games: u32,
+    pub num_territories_per_player: u32,
+    pub num_dice_per_player: u32,
+    pub max_dice: u32,
+    pub max_turns: u32,
+    pub seed: u64,
+}
+
+impl Default for TournamentConfig {
+    fn default() -> TournamentConfig {
+        TournamentConfig {
+            strategies: vec!["random".to_owned(), "greedy".to_owned()],
+            num_games: 1000,
+            num_territories_per_player: 8,
+            num_dice_per_player: 20,
+            max_dice: DEFAULT_MAX_DICE,
+            max_turns: 1000,
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategyResult {
+    pub name: String,
+    pub games: u32,
+    pub wins: u32,
+    pub total_winning_turns: u64,
+}
+
+impl StrategyResult {
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            return 0.0;
+        }
+
+        f64::from(self.wins) / f64::from(self.games)
+    }
+
+    // 95% Wilson score interval, which stays inside [0, 1] for small samples
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        if self.games == 0 {
+            return (0.0, 1.0);
+        }
+
+        let num_games: f64 = f64::from(self.games);
+        let win_rate: f64 = self.win_rate();
+        let z_squared: f64 = CONFIDENCE_Z * CONFIDENCE_Z;
+
+        let denominator: f64 = 1.0 + z_squared / num_games;
+        let center: f64 = (win_rate + z_squared / (2.0 * num_games)) / denominator;
+        let half_width: f64 = CONFIDENCE_Z
+            * (win_rate * (1.0 - win_rate) / num_games + z_squared / (4.0 * num_games * num_games))
+                .sqrt()
+            / denominator;
+
+        (
+            (center - half_width).max(0.0),
+            (center + half_width).min(1.0),
+        )
+    }
+
+    pub fn average_winning_turns(&self) -> f64 {
+        if self.wins == 0 {
+            return 0.0;
+        }
+
+        self.total_winning_turns as f64 / f64::from(self.wins)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TournamentReport {
+    pub num_games: u32,
+    pub num_draws: u32,
+    pub total_turns: u64,
+    pub results: Vec<StrategyResult>,
+}
+
+impl TournamentReport {
+    pub fn average_game_length(&self) -> f64 {
+        if self.num_games == 0 {
+            return 0.0;
+        }
+
+        self.total_turns as f64 / f64::from(self.num_games)
+    }
+
+    pub fn to_table(&self) -> String {
+        let mut table: String = format!(
+            "{} games, {} draws, {:.1} turns per game on average\n",
+            self.num_games,
+            self.num_draws,
+            self.average_game_length()
+        );
+        table.push_str(&format!(
+            "{:<20} {:>8} {:>8} {:>9} {:>17} {:>14}\n",
+            "strategy", "games", "wins", "win rate", "95% CI", "turns to win"
+        ));
+
+        for cur_result in &self.results {
+            let (low, high) = cur_result.confidence_interval();
+            table.push_str(&format!(
+                "{:<20} {:>8} {:>8} {:>8.1}% {:>7.1}% - {:>5.1}% {:>14.1}\n",
+                cur_result.name,
+                cur_result.games,
+                cur_result.wins,
+                cur_result.win_rate() * 100.0,
+                low * 100.0,
+                high * 100.0,
+                cur_result.average_winning_turns()
+            ));
+        }
+
+        table
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv: String =
+            "strategy,games,wins,win_rate,ci_low,ci_high,average_turns_to_win\n".to_owned();
+
+        for cur_result in &self.results {
+            let (low, high) = cur_result.confidence_interval();
+            csv.push_str(&format!(
+                "{},{},{},{:.6},{:.6},{:.6},{:.3}\n",
+                cur_result.name,
+                cur_result.games,
+                cur_result.wins,
+                cur_result.win_rate(),
+                low,
+                high,
+                cur_result.average_winning_turns()
+            ));
+        }
+
+        csv
+    }
+}
+
+// Plays `num_games` bot-only games in-process. Seats rotate every game so no
+// strategy always gets the first move, and game `n` is played on the board
+// generated from `seed + n`. Bots, battles and reinforcements share one rng
+// seeded from `seed`, so a config always gives the same report unless a bot
+// searches for a fixed time.
+
+pub fn run(config: &TournamentConfig) -> Result<TournamentReport, String> {
+    let num_players: u32 = config.strategies.len() as u32;
+    if num_players < 2 {
+        return Err("A tournament needs at least two strategies".to_owned());
+    }
+
+    for cur_name in &config.strategies {
+        if strategy::from_name(cur_name).is_none() {
+            return Err(format!("Unknown bot strategy: {}", cur_name));
+        }
+    }
+
+    let mut board_config: BoardConfig = BoardConfig::new(
+        num_players,
+        config.num_territories_per_player,
+        config.num_dice_per_player,
+    );
+    board_config.max_dice = config.max_dice;
+    board_config.validate()?;
+
+    let mut report = TournamentReport {
+        num_games: config.num_games,
+        num_draws: 0,
+        total_turns: 0,
+        results: Vec::new(),
+    };
+    for cur_name in &config.strategies {
+        if !report.results.iter().any(|result| &result.name == cur_name) {
+            report.results.push(StrategyResult {
+                name: cur_name.to_owned(),
+                games: 0,
+                wins: 0,
+                total_winning_turns: 0,
+            });
+        }
+    }
+
+    let mut rng: StdRng = StdRng::seed_from_u64(config.seed);
+    for cur_game in 0..config.num_games {
+        let seat_names: Vec<&String> = (0..num_players)
+            .map(|cur_seat| &config.strategies[((cur_seat + cur_game) % num_players) as usize])
+            .collect();
+        let mut bots: Vec<Box<dyn Strategy>> = seat_names
+            .iter()
+            .map(|cur_name| strategy::from_name(cur_name).unwrap())
+            .collect();
+
+        board_config.seed = Some(config.seed.wrapping_add(u64::from(cur_game)));
+        let mut gameboard: Gameboard = Gameboard::from_config(&board_config);
+
+        while gameboard.winner().is_none() && gameboard.turn_number <= config.max_turns {
+            let cur_seat: usize = gameboard.current_player_id as usize;
+            strategy::play_turn_with_rng(bots[cur_seat].as_mut(), &mut gameboard, &mut rng);
+        }
+
+        report.total_turns += u64::from(gameboard.turn_number);
+
+        for cur_name in &seat_names {
+            let result: &mut StrategyResult = report
+                .results
+                .iter_mut()
+                .find(|result| &&result.name == cur_name)
+                .unwrap();
+            result.games += 1;
+        }
+
+        match gameboard.winner() {
+            Some(winner) => {
+                let result: &mut StrategyResult = report
+                    .results
+                    .iter_mut()
+                    .find(|result| &result.name == seat_names[winner as usize])
+                    .unwrap();
+                result.wins += 1;
+                result.total_winning_turns += u64::from(gameboard.turn_number);
+            }
+            None => report.num_draws += 1,
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wilson_interval_contains_win_rate() {
+        let result = StrategyResult {
+            name: "greedy".to_owned(),
+            games: 100,
+            wins: 70,
+            total_winning_turns: 0,
+        };
+
+        let (low, high) = result.confidence_interval();
+        assert!(low < 0.7 && 0.7 < high);
+        assert!((low - 0.6041).abs() < 1e-3);
+        assert!((high - 0.7810).abs() < 1e-3);
+    }
+
+    #[test]
+    fn tournament_accounts_for_every_game() {
+        let config = TournamentConfig {
+            strategies: vec!["greedy".to_owned(), "random".to_owned()],
+            num_games: 10,
+            num_territories_per_player: 4,
+            num_dice_per_player: 10,
+            max_turns: 200,
+            ..TournamentConfig::default()
+        };
+
+        let report: TournamentReport = run(&config).unwrap();
+        let total_wins: u32 = report.results.iter().map(|result| result.wins).sum();
+
+        assert_eq!(10, total_wins + report.num_draws);
+        for cur_result in &report.results {
+            assert_eq!(10, cur_result.games);
+        }
+        assert_eq!(3, report.to_csv().lines().count());
+    }
+
+    #[test]
+    fn tournament_replays_with_the_same_seed() {
+        let config = TournamentConfig {
+            strategies: vec!["greedy".to_owned(), "random".to_owned()],
+            num_games: 6,
+            num_territories_per_player: 4,
+            num_dice_per_player: 10,
+            max_dice: 3,
+            max_turns: 200,
+            seed: 11,
+        };
+
+        assert_eq!(run(&config).unwrap(), run(&config).unwrap());
+    }
+
+    #[test]
+    fn tournament_rejects_dice_over_the_cap() {
+        let config = TournamentConfig {
+            num_territories_per_player: 4,
+            num_dice_per_player: 10,
+            max_dice: 2,
+            ..TournamentConfig::default()
+        };
+
+        assert!(run(&config).is_err());
+    }
+
+    #[test]
+    fn tournament_rejects_unknown_strategies() {
+        let config = TournamentConfig {
+            strategies: vec!["greedy".to_owned(), "psychic".to_owned()],
+            ..TournamentConfig::default()
+        };
+
+        assert!(run(&config).is_err());
+    }
+}
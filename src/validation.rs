@@ -0,0 +1,487 @@
+use crate::gameboard::{Gameboard, NEUTRAL_OWNER};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+/// A problem found in a board or map file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnostic {
+    /// `from` lists `to` as a neighbor but not the other way around
+    AsymmetricEdge {
+        from: u32,
+        to: u32,
+    },
+    SelfLoop(u32),
+    DanglingNeighbor {
+        territory: u32,
+        neighbor: u32,
+    },
+    RepeatedNeighbor {
+        territory: u32,
+        neighbor: u32,
+    },
+    /// The territory stored under `key` says its id is `id`
+    MismatchedId {
+        key: u32,
+        id: u32,
+    },
+    /// Several territories (by map key) claim the same id
+    DuplicateId {
+        id: u32,
+        keys: Vec<u32>,
+    },
+    /// Every component of a disconnected board, largest first
+    Disconnected {
+        components: Vec<Vec<u32>>,
+    },
+    NoDice(u32),
+    TooManyDice {
+        territory: u32,
+        num_dice: u32,
+        max_dice: u32,
+    },
+    UnknownOwner {
+        territory: u32,
+        owner: u32,
+    },
+    UnknownContinent {
+        territory: u32,
+        continent: u32,
+    },
+    UnknownCurrentPlayer(u32),
+    /// The team list must name a team for every player
+    TeamCount {
+        num_teams: u32,
+        num_players: u32,
+    },
+    /// Teams are numbered like players, so there are at most as many teams as players
+    UnknownTeam {
+        player: u32,
+        team: u32,
+    },
+    /// Every player with territory is on `team`, so the game is won before anyone moves
+    OneTeam {
+        team: u32,
+    },
+    InvalidRules(String),
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Diagnostic::AsymmetricEdge { from, to } => write!(
+                f,
+                "Territory {} lists {} as a neighbor, but {} does not list {}",
+                from, to, to, from
+            ),
+            Diagnostic::SelfLoop(territory) => {
+                write!(f, "Territory {} lists itself as a neighbor", territory)
+            }
+            Diagnostic::DanglingNeighbor {
+                territory,
+                neighbor,
+            } => write!(
+                f,
+                "Territory {} lists neighbor {}, which does not exist",
+                territory, neighbor
+            ),
+            Diagnostic::RepeatedNeighbor {
+                territory,
+                neighbor,
+            } => write!(
+                f,
+                "Territory {} lists neighbor {} more than once",
+                territory, neighbor
+            ),
+            Diagnostic::MismatchedId { key, id } => {
+                write!(f, "Territory stored under {} has id {}", key, id)
+            }
+            Diagnostic::DuplicateId { id, keys } => write!(
+                f,
+                "Territories stored under {} all have id {}",
+                join_ids(keys),
+                id
+            ),
+            Diagnostic::Disconnected { components } => {
+                write!(f, "The board has {} separate parts:", components.len())?;
+                for cur_component in components {
+                    write!(f, " [{}]", join_ids(cur_component))?;
+                }
+                Ok(())
+            }
+            Diagnostic::NoDice(territory) => write!(f, "Territory {} has no dice", territory),
+            Diagnostic::TooManyDice {
+                territory,
+                num_dice,
+                max_dice,
+            } => write!(
+                f,
+                "Territory {} has {} dice, more than the maximum of {}",
+                territory, num_dice, max_dice
+            ),
+            Diagnostic::UnknownOwner { territory, owner } => write!(
+                f,
+                "Territory {} is owned by player {}, who is not in the game",
+                territory, owner
+            ),
+            Diagnostic::UnknownContinent {
+                territory,
+                continent,
+            } => write!(
+                f,
+                "Territory {} is in continent {}, which is not defined",
+                territory, continent
+            ),
+            Diagnostic::UnknownCurrentPlayer(player_id) => {
+                write!(f, "The current player {} is not in the game", player_id)
+            }
+            Diagnostic::TeamCount {
+                num_teams,
+                num_players,
+            } => write!(
+                f,
+                "The board lists teams for {} players, but {} are in the game",
+                num_teams, num_players
+            ),
+            Diagnostic::UnknownTeam { player, team } => write!(
+                f,
+                "Player {} is on team {}, but teams are numbered like the players",
+                player, team
+            ),
+            Diagnostic::OneTeam { team } => write!(
+                f,
+                "Every player with territory is on team {}, so the game is already over",
+                team
+            ),
+            Diagnostic::InvalidRules(message) => write!(f, "Invalid rules: {}", message),
+        }
+    }
+}
+
+// Every problem with `gameboard`, in a stable order. An empty list means the board is playable.
+pub fn validate(gameboard: &Gameboard) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+    let mut all_keys: Vec<u32> = gameboard.territory_map.keys().copied().collect();
+    all_keys.sort_unstable();
+
+    let mut keys_by_id: HashMap<u32, Vec<u32>> = HashMap::new();
+    for cur_key in &all_keys {
+        let cur_terr_id: u32 = gameboard.territory_map[cur_key].id;
+        if cur_terr_id != *cur_key {
+            diagnostics.push(Diagnostic::MismatchedId {
+                key: *cur_key,
+                id: cur_terr_id,
+            });
+        }
+        keys_by_id.entry(cur_terr_id).or_default().push(*cur_key);
+    }
+
+    let mut duplicate_ids: Vec<(u32, Vec<u32>)> = keys_by_id
+        .into_iter()
+        .filter(|(_id, keys)| keys.len() > 1)
+        .collect();
+    duplicate_ids.sort_unstable();
+    for (cur_id, cur_keys) in duplicate_ids {
+        diagnostics.push(Diagnostic::DuplicateId {
+            id: cur_id,
+            keys: cur_keys,
+        });
+    }
+
+    let continent_ids: HashSet<u32> = gameboard
+        .continents
+        .iter()
+        .map(|continent| continent.id)
+        .collect();
+
+    for cur_key in &all_keys {
+        let cur_terr = &gameboard.territory_map[cur_key];
+        let mut seen_neighbors: HashSet<u32> = HashSet::new();
+
+        for cur_neighbor in &cur_terr.neighbors {
+            if !seen_neighbors.insert(*cur_neighbor) {
+                diagnostics.push(Diagnostic::RepeatedNeighbor {
+                    territory: *cur_key,
+                    neighbor: *cur_neighbor,
+                });
+            } else if cur_neighbor == cur_key {
+                diagnostics.push(Diagnostic::SelfLoop(*cur_key));
+            } else {
+                match gameboard.territory_map.get(cur_neighbor) {
+                    None => diagnostics.push(Diagnostic::DanglingNeighbor {
+                        territory: *cur_key,
+                        neighbor: *cur_neighbor,
+                    }),
+                    Some(neighbor_terr) if !neighbor_terr.neighbors.contains(cur_key) => {
+                        diagnostics.push(Diagnostic::AsymmetricEdge {
+                            from: *cur_key,
+                            to: *cur_neighbor,
+                        })
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        if cur_terr.num_dice == 0 {
+            diagnostics.push(Diagnostic::NoDice(*cur_key));
+        } else if cur_terr.num_dice > gameboard.max_dice {
+            diagnostics.push(Diagnostic::TooManyDice {
+                territory: *cur_key,
+                num_dice: cur_terr.num_dice,
+                max_dice: gameboard.max_dice,
+            });
+        }
+
+        if cur_terr.owner_id >= gameboard.num_players && cur_terr.owner_id != NEUTRAL_OWNER {
+            diagnostics.push(Diagnostic::UnknownOwner {
+                territory: *cur_key,
+                owner: cur_terr.owner_id,
+            });
+        }
+
+        if let Some(continent) = cur_terr.continent {
+            if !continent_ids.contains(&continent) {
+                diagnostics.push(Diagnostic::UnknownContinent {
+                    territory: *cur_key,
+                    continent,
+                });
+            }
+        }
+    }
+
+    if gameboard.current_player_id >= gameboard.num_players {
+        diagnostics.push(Diagnostic::UnknownCurrentPlayer(
+            gameboard.current_player_id,
+        ));
+    }
+
+    if !gameboard.teams.is_empty() && gameboard.teams.len() != gameboard.num_players as usize {
+        diagnostics.push(Diagnostic::TeamCount {
+            num_teams: gameboard.teams.len() as u32,
+            num_players: gameboard.num_players,
+        });
+    }
+
+    for (cur_player, cur_team) in gameboard.teams.iter().enumerate() {
+        if *cur_team >= gameboard.num_players {
+            diagnostics.push(Diagnostic::UnknownTeam {
+                player: cur_player as u32,
+                team: *cur_team,
+            });
+        }
+    }
+
+    if !gameboard.teams.is_empty() && gameboard.num_teams_in_play() == 1 {
+        let team: Option<u32> = gameboard
+            .territory_map
+            .values()
+            .find(|cur_terr| cur_terr.owner_id != NEUTRAL_OWNER)
+            .map(|cur_terr| gameboard.team_of(cur_terr.owner_id));
+        if let Some(team) = team {
+            diagnostics.push(Diagnostic::OneTeam { team });
+        }
+    }
+
+    if let Err(message) = gameboard.rules.validate() {
+        diagnostics.push(Diagnostic::InvalidRules(message));
+    }
+
+    let components: Vec<Vec<u32>> = connected_components(gameboard);
+    if components.len() > 1 {
+        diagnostics.push(Diagnostic::Disconnected { components });
+    }
+
+    diagnostics
+}
+
+// Components over edges that exist in both directions, largest first
+pub fn connected_components(gameboard: &Gameboard) -> Vec<Vec<u32>> {
+    let mut all_keys: Vec<u32> = gameboard.territory_map.keys().copied().collect();
+    all_keys.sort_unstable();
+
+    let mut visited: HashSet<u32> = HashSet::new();
+    let mut components: Vec<Vec<u32>> = Vec::new();
+
+    for cur_key in all_keys {
+        if !visited.insert(cur_key) {
+            continue;
+        }
+
+        let mut component: Vec<u32> = Vec::new();
+        let mut queue: VecDeque<u32> = VecDeque::new();
+        queue.push_back(cur_key);
+
+        while let Some(cur_id) = queue.pop_front() {
+            component.push(cur_id);
+
+            for cur_neighbor in &gameboard.territory_map[&cur_id].neighbors {
+                let is_mutual: bool = gameboard
+                    .territory_map
+                    .get(cur_neighbor)
+                    .is_some_and(|neighbor_terr| neighbor_terr.neighbors.contains(&cur_id));
+                if is_mutual && visited.insert(*cur_neighbor) {
+                    queue.push_back(*cur_neighbor);
+                }
+            }
+        }
+
+        component.sort_unstable();
+        components.push(component);
+    }
+
+    components.sort_by(|first, second| second.len().cmp(&first.len()).then(first.cmp(second)));
+    components
+}
+
+fn join_ids(ids: &[u32]) -> String {
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameboard::Territory;
+
+    fn territory(id: u32, owner_id: u32, neighbors: Vec<u32>) -> Territory {
+        Territory {
+            id,
+            num_dice: 2,
+            owner_id,
+            neighbors,
+            ..Default::default()
+        }
+    }
+
+    fn board(territories: Vec<(u32, Territory)>) -> Gameboard {
+        Gameboard::from_territory_map(territories.into_iter().collect(), 2)
+    }
+
+    #[test]
+    fn generated_board_is_valid() {
+        let gameboard: Gameboard = Gameboard::new_seeded(3, 6, 14, 2);
+
+        assert_eq!(Vec::<Diagnostic>::new(), validate(&gameboard));
+    }
+
+    #[test]
+    fn reports_every_edge_problem() {
+        let gameboard: Gameboard = board(vec![
+            (0, territory(0, 0, vec![1, 0, 7])),
+            (1, territory(1, 1, vec![2])),
+            (2, territory(2, 0, vec![1, 1])),
+        ]);
+
+        assert_eq!(
+            vec![
+                Diagnostic::AsymmetricEdge { from: 0, to: 1 },
+                Diagnostic::SelfLoop(0),
+                Diagnostic::DanglingNeighbor {
+                    territory: 0,
+                    neighbor: 7
+                },
+                Diagnostic::RepeatedNeighbor {
+                    territory: 2,
+                    neighbor: 1
+                },
+                Diagnostic::Disconnected {
+                    components: vec![vec![1, 2], vec![0]]
+                },
+            ],
+            validate(&gameboard)
+        );
+    }
+
+    #[test]
+    fn reports_ids_dice_and_owners() {
+        let mut too_many_dice: Territory = territory(1, 5, vec![0]);
+        too_many_dice.num_dice = 9;
+        let mut no_dice: Territory = territory(1, 0, vec![0]);
+        no_dice.num_dice = 0;
+        no_dice.continent = Some(3);
+
+        let gameboard: Gameboard = board(vec![
+            (0, territory(0, 0, vec![1, 2])),
+            (1, too_many_dice),
+            (2, no_dice),
+        ]);
+
+        assert_eq!(
+            vec![
+                Diagnostic::MismatchedId { key: 2, id: 1 },
+                Diagnostic::DuplicateId {
+                    id: 1,
+                    keys: vec![1, 2]
+                },
+                Diagnostic::TooManyDice {
+                    territory: 1,
+                    num_dice: 9,
+                    max_dice: 8
+                },
+                Diagnostic::UnknownOwner {
+                    territory: 1,
+                    owner: 5
+                },
+                Diagnostic::NoDice(2),
+                Diagnostic::UnknownContinent {
+                    territory: 2,
+                    continent: 3
+                },
+            ],
+            validate(&gameboard)
+        );
+    }
+
+    #[test]
+    fn reports_invalid_rules() {
+        let mut gameboard: Gameboard = Gameboard::new_seeded(2, 3, 6, 1);
+        gameboard.rules.dice.die_faces = 0;
+
+        assert_eq!(
+            vec![Diagnostic::InvalidRules(
+                "Dice need at least 2 faces, not 0".to_string()
+            )],
+            validate(&gameboard)
+        );
+    }
+
+    #[test]
+    fn reports_a_team_for_every_player() {
+        let mut gameboard: Gameboard = Gameboard::new_seeded(3, 3, 6, 1);
+        gameboard.teams = vec![0, 3];
+
+        assert_eq!(
+            vec![
+                Diagnostic::TeamCount {
+                    num_teams: 2,
+                    num_players: 3
+                },
+                Diagnostic::UnknownTeam { player: 1, team: 3 },
+            ],
+            validate(&gameboard)
+        );
+    }
+
+    #[test]
+    fn reports_a_single_team() {
+        let mut gameboard: Gameboard = Gameboard::new_seeded(2, 3, 6, 1);
+        gameboard.teams = vec![1, 1];
+
+        assert_eq!(vec![Diagnostic::OneTeam { team: 1 }], validate(&gameboard));
+    }
+
+    #[test]
+    fn diagnostics_name_the_territories() {
+        let diagnostic = Diagnostic::Disconnected {
+            components: vec![vec![0, 1], vec![2]],
+        };
+
+        assert_eq!(
+            "The board has 2 separate parts: [0, 1] [2]",
+            diagnostic.to_string()
+        );
+    }
+}